@@ -0,0 +1,73 @@
+//! Meshes and materials shared by every card and shot, so spawning does not add assets.
+
+use bevy::prelude::*;
+use bevy::utils::HashMap;
+
+use crate::solitaire::CARD_SIZE;
+use crate::weapon::ShotKind;
+
+/// One card quad, a mesh and material per kind of shot, and one material per card look.
+#[derive(Resource)]
+pub struct GameAssets {
+    pub card_mesh: Handle<Mesh>,
+    shots: Vec<(ShotKind, Handle<Mesh>, Handle<StandardMaterial>)>,
+    /// Keyed by texture path and tint.
+    card_materials: HashMap<(String, u32), Handle<StandardMaterial>>,
+}
+
+impl FromWorld for GameAssets {
+    fn from_world(world: &mut World) -> Self {
+        let card_mesh = world.resource_mut::<Assets<Mesh>>()
+            .add(Mesh::from(shape::Quad::new(Vec2::new(CARD_SIZE, CARD_SIZE))));
+        let shots = ShotKind::ALL.into_iter()
+            .map(|kind| {
+                let size = kind.size();
+                let mesh = world.resource_mut::<Assets<Mesh>>()
+                    .add(Mesh::from(shape::Box::new(size.x, size.y, size.z)));
+                let material = world.resource_mut::<Assets<StandardMaterial>>()
+                    .add(StandardMaterial {
+                        base_color: kind.color(),
+                        emissive: kind.color(),
+                        ..Default::default()
+                    });
+                (kind, mesh, material)
+            })
+            .collect();
+        Self {
+            card_mesh,
+            shots,
+            card_materials: HashMap::default(),
+        }
+    }
+}
+
+impl GameAssets {
+    /// Mesh and material of a card showing the texture at `texture_path`, tinted by `tint`.
+    /// The material is made on the first request and shared from then on.
+    pub fn card_look(
+        &mut self,
+        texture_path: &str,
+        tint: Color,
+        asset_server: &AssetServer,
+        materials: &mut Assets<StandardMaterial>,
+    ) -> (Handle<Mesh>, Handle<StandardMaterial>) {
+        let material = self.card_materials
+            .entry((texture_path.to_string(), tint.as_rgba_u32()))
+            .or_insert_with(|| materials.add(StandardMaterial {
+                base_color: tint,
+                base_color_texture: Some(asset_server.load(texture_path)),
+                alpha_mode: AlphaMode::Blend,
+                unlit: true,
+                ..Default::default()
+            }))
+            .clone();
+        (self.card_mesh.clone(), material)
+    }
+
+    pub fn shot(&self, kind: ShotKind) -> (Handle<Mesh>, Handle<StandardMaterial>) {
+        self.shots.iter()
+            .find(|(k, _, _)| *k == kind)
+            .map(|(_, mesh, material)| (mesh.clone(), material.clone()))
+            .expect("every kind of shot has its assets")
+    }
+}
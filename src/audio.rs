@@ -0,0 +1,194 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::controls::{Action, Actions};
+use crate::game::GameState;
+use crate::storage;
+
+const AUDIO_KEY: &str = "audio";
+
+/// Short sounds of the game, each played once per [`SoundEvent`].
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum SoundEffect {
+    Laser,
+    Hit,
+    Miss,
+    /// A card fell out of the play field.
+    CardLost,
+    GameOver,
+}
+
+impl SoundEffect {
+    fn path(&self) -> &'static str {
+        match self {
+            SoundEffect::Laser => "sounds/laser.wav",
+            SoundEffect::Hit => "sounds/hit.wav",
+            SoundEffect::Miss => "sounds/miss.wav",
+            SoundEffect::CardLost => "sounds/card_lost.wav",
+            SoundEffect::GameOver => "sounds/game_over.wav",
+        }
+    }
+}
+
+/// Plays a sound effect at the effect volume.
+pub struct SoundEvent(pub SoundEffect);
+
+/// Volumes from 0 to 1, kept between runs.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct AudioSettings {
+    pub master: f32,
+    pub music: f32,
+    pub effects: f32,
+    #[serde(default)]
+    pub muted: bool,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master: 0.8,
+            music: 0.5,
+            effects: 1.0,
+            muted: false,
+        }
+    }
+}
+
+/// Volume change of a single step on the settings page.
+pub const VOLUME_STEP: f32 = 0.1;
+
+impl AudioSettings {
+    pub fn music_volume(&self) -> f32 {
+        if self.muted { 0.0 } else { self.master * self.music }
+    }
+
+    pub fn effects_volume(&self) -> f32 {
+        if self.muted { 0.0 } else { self.master * self.effects }
+    }
+
+    /// Moves a volume by `step` volume steps, staying between 0 and 1.
+    pub fn change(volume: &mut f32, step: i32) {
+        *volume = ((*volume / VOLUME_STEP).round() + step as f32).clamp(0.0, 1.0 / VOLUME_STEP) * VOLUME_STEP;
+    }
+
+    pub fn load() -> Self {
+        storage::load(AUDIO_KEY)
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let result = serde_json::to_string(self)
+            .map_err(|e| e.to_string())
+            .and_then(|text| storage::save(AUDIO_KEY, &text));
+        if let Err(err) = result {
+            error!("could not save audio settings: {}", err);
+        }
+    }
+}
+
+/// The loop playing for the current [`GameState`].
+#[derive(Resource, Default)]
+struct Music {
+    track: Option<&'static str>,
+    sink: Handle<AudioSink>,
+}
+
+/// Background loop of a state, the pause menu keeps the loop of the run.
+fn music_track(state: GameState) -> Option<&'static str> {
+    match state {
+        GameState::GameStart | GameState::Controls => Some("music/title.wav"),
+        GameState::Playing | GameState::Paused => Some("music/playing.wav"),
+        GameState::GameOver => None,
+    }
+}
+
+/// Sound effects, music per game state and the volume settings. Needs bevy's `AudioPlugin`.
+pub struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<SoundEvent>()
+            .init_resource::<AudioSettings>()
+            .init_resource::<Music>()
+            .add_system(game_over_sound.in_schedule(OnEnter(GameState::GameOver)))
+            .add_systems((play_sounds, toggle_mute, play_music, music_volume).chain());
+    }
+}
+
+fn game_over_sound(mut event_sound: EventWriter<SoundEvent>) {
+    event_sound.send(SoundEvent(SoundEffect::GameOver));
+}
+
+fn play_sounds(
+    mut events: EventReader<SoundEvent>,
+    settings: Res<AudioSettings>,
+    audio: Res<Audio>,
+    asset_server: Res<AssetServer>,
+) {
+    let volume = settings.effects_volume();
+    for SoundEvent(effect) in events.iter() {
+        if volume > 0.0 {
+            audio.play_with_settings(asset_server.load(effect.path()),
+                                     PlaybackSettings::ONCE.with_volume(volume));
+        }
+    }
+}
+
+fn toggle_mute(
+    actions: Actions,
+    mut settings: ResMut<AudioSettings>,
+) {
+    if actions.just_pressed(Action::Mute) {
+        settings.muted = !settings.muted;
+        settings.save();
+    }
+}
+
+/// Changes the loop when the state asks for another one, pausing it with the game.
+fn play_music(
+    state: Res<State<GameState>>,
+    settings: Res<AudioSettings>,
+    mut music: ResMut<Music>,
+    audio: Res<Audio>,
+    sinks: Res<Assets<AudioSink>>,
+    asset_server: Res<AssetServer>,
+) {
+    if !state.is_changed() {
+        return;
+    }
+    let track = music_track(state.0);
+    if track != music.track {
+        if let Some(sink) = sinks.get(&music.sink) {
+            sink.stop();
+        }
+        music.sink = match track {
+            Some(path) => {
+                let sink = audio.play_with_settings(asset_server.load(path),
+                                                    PlaybackSettings::LOOP.with_volume(settings.music_volume()));
+                sinks.get_handle(sink)
+            }
+            None => Handle::default(),
+        };
+        music.track = track;
+    }
+    if let Some(sink) = sinks.get(&music.sink) {
+        if state.0 == GameState::Paused {
+            sink.pause();
+        } else {
+            sink.play();
+        }
+    }
+}
+
+fn music_volume(
+    settings: Res<AudioSettings>,
+    music: Res<Music>,
+    sinks: Res<Assets<AudioSink>>,
+) {
+    if settings.is_changed() {
+        if let Some(sink) = sinks.get(&music.sink) {
+            sink.set_volume(settings.music_volume());
+        }
+    }
+}
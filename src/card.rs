@@ -0,0 +1,222 @@
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum Suit {
+    Hearts,
+    Diamonds,
+    Clubs,
+    Spades,
+}
+
+impl Suit {
+    pub const ALL: [Suit; 4] = [Suit::Hearts, Suit::Diamonds, Suit::Clubs, Suit::Spades];
+
+    pub fn color(&self) -> CardColor {
+        match self {
+            Suit::Hearts | Suit::Diamonds => CardColor::Red,
+            Suit::Clubs | Suit::Spades => CardColor::Black,
+        }
+    }
+
+    fn name(&self) -> &'static str {
+        match self {
+            Suit::Hearts => "hearts",
+            Suit::Diamonds => "diamonds",
+            Suit::Clubs => "clubs",
+            Suit::Spades => "spades",
+        }
+    }
+
+    fn from_name(name: &str) -> Option<Self> {
+        Suit::ALL.into_iter().find(|s| s.name() == name)
+    }
+}
+
+impl fmt::Display for Suit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum CardColor {
+    Red,
+    Black,
+}
+
+impl CardColor {
+    fn name(&self) -> &'static str {
+        match self {
+            CardColor::Red => "red",
+            CardColor::Black => "black",
+        }
+    }
+}
+
+impl fmt::Display for CardColor {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Ranks in ascending order, ace high.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, PartialOrd, Ord)]
+pub enum Rank {
+    Two,
+    Three,
+    Four,
+    Five,
+    Six,
+    Seven,
+    Eight,
+    Nine,
+    Ten,
+    Jack,
+    Queen,
+    King,
+    Ace,
+}
+
+impl Rank {
+    pub const ALL: [Rank; 13] = [Rank::Two, Rank::Three, Rank::Four, Rank::Five, Rank::Six,
+                                 Rank::Seven, Rank::Eight, Rank::Nine, Rank::Ten, Rank::Jack,
+                                 Rank::Queen, Rank::King, Rank::Ace];
+
+    /// Short label as shown to the player, e.g. "2", "10" or "J".
+    pub fn label(&self) -> &'static str {
+        match self {
+            Rank::Two => "2",
+            Rank::Three => "3",
+            Rank::Four => "4",
+            Rank::Five => "5",
+            Rank::Six => "6",
+            Rank::Seven => "7",
+            Rank::Eight => "8",
+            Rank::Nine => "9",
+            Rank::Ten => "10",
+            Rank::Jack => "J",
+            Rank::Queen => "Q",
+            Rank::King => "K",
+            Rank::Ace => "A",
+        }
+    }
+
+    /// Rank part of a Kenney texture name, number ranks are zero padded.
+    fn texture_code(&self) -> String {
+        match self {
+            Rank::Ten | Rank::Jack | Rank::Queen | Rank::King | Rank::Ace => self.label().to_string(),
+            _ => format!("0{}", self.label()),
+        }
+    }
+
+    fn from_texture_code(code: &str) -> Option<Self> {
+        Rank::ALL.into_iter().find(|r| r.texture_code() == code)
+    }
+}
+
+impl fmt::Display for Rank {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+/// A card face as printed on the Kenney textures.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash)]
+pub enum PlayingCard {
+    Regular { suit: Suit, rank: Rank },
+    Joker(CardColor),
+}
+
+impl PlayingCard {
+    pub fn new(suit: Suit, rank: Rank) -> Self {
+        PlayingCard::Regular { suit, rank }
+    }
+
+    pub fn rank(&self) -> Option<Rank> {
+        match self {
+            PlayingCard::Regular { rank, .. } => Some(*rank),
+            PlayingCard::Joker(_) => None,
+        }
+    }
+
+    pub fn suit(&self) -> Option<Suit> {
+        match self {
+            PlayingCard::Regular { suit, .. } => Some(*suit),
+            PlayingCard::Joker(_) => None,
+        }
+    }
+
+    pub fn color(&self) -> CardColor {
+        match self {
+            PlayingCard::Regular { suit, .. } => suit.color(),
+            PlayingCard::Joker(color) => *color,
+        }
+    }
+
+    pub fn is_joker(&self) -> bool {
+        matches!(self, PlayingCard::Joker(_))
+    }
+
+    /// File name of the texture without extension, e.g. "card_hearts_02".
+    pub fn texture_name(&self) -> String {
+        match self {
+            PlayingCard::Regular { suit, rank } => format!("card_{}_{}", suit, rank.texture_code()),
+            PlayingCard::Joker(color) => format!("card_joker_{}", color),
+        }
+    }
+
+    pub fn texture_path(&self) -> String {
+        format!("cards/{}.png", self.texture_name())
+    }
+}
+
+impl fmt::Display for PlayingCard {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.texture_name())
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ParseCardError {
+    MissingPrefix(String),
+    UnknownSuit(String),
+    UnknownRank(String),
+    UnknownJoker(String),
+}
+
+impl fmt::Display for ParseCardError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseCardError::MissingPrefix(s) => write!(f, "card name '{}' does not start with 'card_'", s),
+            ParseCardError::UnknownSuit(s) => write!(f, "unknown suit '{}'", s),
+            ParseCardError::UnknownRank(s) => write!(f, "unknown rank '{}'", s),
+            ParseCardError::UnknownJoker(s) => write!(f, "unknown joker color '{}'", s),
+        }
+    }
+}
+
+impl std::error::Error for ParseCardError {}
+
+impl FromStr for PlayingCard {
+    type Err = ParseCardError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rest = s
+            .strip_prefix("card_")
+            .ok_or_else(|| ParseCardError::MissingPrefix(s.to_string()))?;
+        let (kind, value) = rest
+            .split_once('_')
+            .ok_or_else(|| ParseCardError::UnknownSuit(rest.to_string()))?;
+        if kind == "joker" {
+            return match value {
+                "red" => Ok(PlayingCard::Joker(CardColor::Red)),
+                "black" => Ok(PlayingCard::Joker(CardColor::Black)),
+                _ => Err(ParseCardError::UnknownJoker(value.to_string())),
+            };
+        }
+        let suit = Suit::from_name(kind).ok_or_else(|| ParseCardError::UnknownSuit(kind.to_string()))?;
+        let rank = Rank::from_texture_code(value).ok_or_else(|| ParseCardError::UnknownRank(value.to_string()))?;
+        Ok(PlayingCard::new(suit, rank))
+    }
+}
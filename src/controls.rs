@@ -0,0 +1,583 @@
+use std::fmt;
+use bevy::prelude::*;
+use bevy::ecs::system::SystemParam;
+use bevy_mod_raycast::RaycastSource;
+use serde::{Deserialize, Serialize};
+
+use crate::game::GameState;
+use crate::replay::{ReplayEvent, ReplayPlayer, ReplayRecorder};
+use crate::ship::{spawn_laser, CreateLaserEvent, FlightMode, Ship, Steering};
+use crate::storage;
+use crate::weapon::{Arsenal, SingleLaser, Weapon, WeaponKind};
+use crate::world::{MyRaycastSet, WHITEBOARD_POSITION, WHITEBOARD_SIZE};
+
+const CONTROLS_KEY: &str = "controls";
+
+/// Everything the player can do, whatever device it is bound to.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Action {
+    Fire,
+    AimUp,
+    AimDown,
+    AimLeft,
+    AimRight,
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    SwitchWeapon,
+    Mute,
+    Pause,
+    Restart,
+    WatchReplay,
+    MenuUp,
+    MenuDown,
+    MenuLeft,
+    MenuRight,
+    Confirm,
+    Back,
+    Controls,
+}
+
+impl Action {
+    pub const ALL: [Action; 21] = [
+        Action::Fire, Action::AimUp, Action::AimDown, Action::AimLeft, Action::AimRight,
+        Action::MoveUp, Action::MoveDown, Action::MoveLeft, Action::MoveRight, Action::SwitchWeapon,
+        Action::Mute, Action::Pause, Action::Restart, Action::WatchReplay,
+        Action::MenuUp, Action::MenuDown, Action::MenuLeft, Action::MenuRight,
+        Action::Confirm, Action::Back, Action::Controls,
+    ];
+
+    pub fn label(&self) -> &'static str {
+        match self {
+            Action::Fire => "fire",
+            Action::AimUp => "aim up",
+            Action::AimDown => "aim down",
+            Action::AimLeft => "aim left",
+            Action::AimRight => "aim right",
+            Action::MoveUp => "move up",
+            Action::MoveDown => "move down",
+            Action::MoveLeft => "move left",
+            Action::MoveRight => "move right",
+            Action::SwitchWeapon => "switch weapon",
+            Action::Mute => "mute",
+            Action::Pause => "pause",
+            Action::Restart => "restart",
+            Action::WatchReplay => "watch replay",
+            Action::MenuUp => "menu up",
+            Action::MenuDown => "menu down",
+            Action::MenuLeft => "menu left",
+            Action::MenuRight => "menu right",
+            Action::Confirm => "confirm",
+            Action::Back => "back",
+            Action::Controls => "controls",
+        }
+    }
+}
+
+impl fmt::Display for Action {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.label())
+    }
+}
+
+/// A single key or button an action is bound to.
+#[derive(Debug, Clone, Copy, Eq, PartialEq, Serialize, Deserialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Mouse(MouseButton),
+    Gamepad(GamepadButtonType),
+}
+
+impl Binding {
+    pub fn scheme(&self) -> ControlScheme {
+        match self {
+            Binding::Key(_) => ControlScheme::Keyboard,
+            Binding::Mouse(_) => ControlScheme::Mouse,
+            Binding::Gamepad(_) => ControlScheme::Gamepad,
+        }
+    }
+}
+
+impl fmt::Display for Binding {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Binding::Key(key) => write!(f, "{:?}", key),
+            Binding::Mouse(button) => write!(f, "mouse {:?}", button),
+            Binding::Gamepad(button) => write!(f, "pad {:?}", button),
+        }
+    }
+}
+
+/// Which stick moves the aim on a gamepad, the other one flies the ship.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum AimStick {
+    #[default]
+    Left,
+    Right,
+}
+
+/// The bindings of every action, edited on the controls screen.
+#[derive(Resource, Debug, Clone, Serialize, Deserialize)]
+pub struct InputMap {
+    pub bindings: Vec<(Action, Vec<Binding>)>,
+    #[serde(default)]
+    pub aim_stick: AimStick,
+}
+
+impl Default for InputMap {
+    fn default() -> Self {
+        use Binding::*;
+        let bindings = Action::ALL.into_iter().map(|action| {
+            let bindings = match action {
+                Action::Fire => vec![Mouse(MouseButton::Left), Key(KeyCode::Space),
+                                     Gamepad(GamepadButtonType::RightTrigger2)],
+                Action::AimUp => vec![Key(KeyCode::Up), Gamepad(GamepadButtonType::DPadUp)],
+                Action::AimDown => vec![Key(KeyCode::Down), Gamepad(GamepadButtonType::DPadDown)],
+                Action::AimLeft => vec![Key(KeyCode::Left), Gamepad(GamepadButtonType::DPadLeft)],
+                Action::AimRight => vec![Key(KeyCode::Right), Gamepad(GamepadButtonType::DPadRight)],
+                Action::MoveUp => vec![Key(KeyCode::W)],
+                Action::MoveDown => vec![Key(KeyCode::S)],
+                Action::MoveLeft => vec![Key(KeyCode::A)],
+                Action::MoveRight => vec![Key(KeyCode::D)],
+                Action::SwitchWeapon => vec![Key(KeyCode::Q), Gamepad(GamepadButtonType::RightTrigger)],
+                Action::Mute => vec![Key(KeyCode::M)],
+                Action::Pause => vec![Key(KeyCode::Escape), Key(KeyCode::P), Gamepad(GamepadButtonType::Start)],
+                Action::Restart => vec![Key(KeyCode::N), Gamepad(GamepadButtonType::West)],
+                Action::WatchReplay => vec![Key(KeyCode::R), Gamepad(GamepadButtonType::North)],
+                Action::MenuUp => vec![Key(KeyCode::Up), Gamepad(GamepadButtonType::DPadUp)],
+                Action::MenuDown => vec![Key(KeyCode::Down), Gamepad(GamepadButtonType::DPadDown)],
+                Action::MenuLeft => vec![Key(KeyCode::Left), Gamepad(GamepadButtonType::DPadLeft)],
+                Action::MenuRight => vec![Key(KeyCode::Right), Gamepad(GamepadButtonType::DPadRight)],
+                Action::Confirm => vec![Key(KeyCode::Return), Key(KeyCode::Space), Gamepad(GamepadButtonType::South)],
+                Action::Back => vec![Key(KeyCode::Escape), Key(KeyCode::Back), Gamepad(GamepadButtonType::East)],
+                Action::Controls => vec![Key(KeyCode::C), Gamepad(GamepadButtonType::Select)],
+            };
+            (action, bindings)
+        }).collect();
+        Self {
+            bindings,
+            aim_stick: AimStick::default()
+        }
+    }
+}
+
+impl InputMap {
+    pub fn load() -> Self {
+        storage::load(CONTROLS_KEY)
+            .and_then(|text| match serde_json::from_str::<InputMap>(&text) {
+                Ok(map) => Some(map.with_missing_actions()),
+                Err(err) => {
+                    warn!("ignoring broken controls: {}", err);
+                    None
+                }
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let result = serde_json::to_string_pretty(self)
+            .map_err(|e| e.to_string())
+            .and_then(|text| storage::save(CONTROLS_KEY, &text));
+        if let Err(err) = result {
+            error!("could not save controls: {}", err);
+        }
+    }
+
+    /// Actions added after the file was saved get their default bindings.
+    fn with_missing_actions(mut self) -> Self {
+        for (action, bindings) in InputMap::default().bindings {
+            if !self.bindings.iter().any(|(a, _)| *a == action) {
+                self.bindings.push((action, bindings));
+            }
+        }
+        self
+    }
+
+    pub fn bindings(&self, action: Action) -> &[Binding] {
+        self.bindings.iter()
+            .find(|(a, _)| *a == action)
+            .map(|(_, bindings)| bindings.as_slice())
+            .unwrap_or_default()
+    }
+
+    /// The first binding of `action`, for hints on screen.
+    pub fn describe(&self, action: Action) -> String {
+        self.bindings(action).first()
+            .map(|binding| binding.to_string())
+            .unwrap_or_else(|| "(unbound)".to_string())
+    }
+
+    /// Binds `binding` to `action`, replacing the action's binding on the same device.
+    pub fn rebind(&mut self, action: Action, binding: Binding) {
+        let scheme = binding.scheme();
+        match self.bindings.iter_mut().find(|(a, _)| *a == action) {
+            Some((_, bindings)) => {
+                bindings.retain(|b| b.scheme() != scheme);
+                bindings.insert(0, binding);
+            }
+            None => self.bindings.push((action, vec![binding])),
+        }
+    }
+}
+
+/// Reads actions through the [`InputMap`] from every device.
+#[derive(SystemParam)]
+pub struct Actions<'w> {
+    map: Res<'w, InputMap>,
+    keys: Res<'w, Input<KeyCode>>,
+    mouse: Res<'w, Input<MouseButton>>,
+    gamepads: Res<'w, Gamepads>,
+    gamepad_buttons: Res<'w, Input<GamepadButton>>,
+}
+
+impl<'w> Actions<'w> {
+    fn check(&self, action: Action,
+             key: impl Fn(&Input<KeyCode>, KeyCode) -> bool,
+             mouse: impl Fn(&Input<MouseButton>, MouseButton) -> bool,
+             pad: impl Fn(&Input<GamepadButton>, GamepadButton) -> bool) -> Option<ControlScheme> {
+        self.map.bindings(action).iter().find(|binding| match binding {
+            Binding::Key(code) => key(&self.keys, *code),
+            Binding::Mouse(button) => mouse(&self.mouse, *button),
+            Binding::Gamepad(button_type) => self.gamepads.iter()
+                .any(|gamepad| pad(&self.gamepad_buttons, GamepadButton::new(gamepad, *button_type))),
+        }).map(Binding::scheme)
+    }
+
+    /// The device the action is held on, if any.
+    pub fn pressed_by(&self, action: Action) -> Option<ControlScheme> {
+        self.check(action, |i, k| i.pressed(k), |i, b| i.pressed(b), |i, b| i.pressed(b))
+    }
+
+    /// The device the action was triggered with this frame, if any.
+    pub fn just_pressed_by(&self, action: Action) -> Option<ControlScheme> {
+        self.check(action, |i, k| i.just_pressed(k), |i, b| i.just_pressed(b), |i, b| i.just_pressed(b))
+    }
+
+    pub fn pressed(&self, action: Action) -> bool {
+        self.pressed_by(action).is_some()
+    }
+
+    pub fn just_pressed(&self, action: Action) -> bool {
+        self.just_pressed_by(action).is_some()
+    }
+
+    pub fn aim_stick(&self) -> AimStick {
+        self.map.aim_stick
+    }
+
+    /// First key or button pressed this frame on any device, for rebinding.
+    pub fn any_just_pressed(&self) -> Option<Binding> {
+        self.keys.get_just_pressed().next().map(|key| Binding::Key(*key))
+            .or_else(|| self.mouse.get_just_pressed().next().map(|button| Binding::Mouse(*button)))
+            .or_else(|| self.gamepad_buttons.get_just_pressed().next()
+                .map(|button| Binding::Gamepad(button.button_type)))
+    }
+}
+
+/// The device the player aimed with last.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum ControlScheme {
+    #[default]
+    Mouse,
+    Keyboard,
+    Gamepad,
+}
+
+/// Point on the whiteboard the ship aims at, whatever device moved it.
+#[derive(Resource)]
+pub struct Aim {
+    pub target: Vec3,
+    pub scheme: ControlScheme,
+}
+
+impl Default for Aim {
+    fn default() -> Self {
+        Self {
+            target: aim_plane_center(),
+            scheme: ControlScheme::default()
+        }
+    }
+}
+
+/// The trigger is held this frame, the [`Weapon`] decides whether a laser leaves
+/// for the current [`Aim`] target.
+pub struct FireEvent;
+
+/// Systems reading the player's devices, left out while a replay plays.
+#[derive(SystemSet, Debug, Clone, Eq, PartialEq, Hash)]
+pub struct PlayerInputSet;
+
+#[derive(Component)]
+struct Reticle;
+
+/// Mouse, keyboard and gamepad schemes, all turned into the same aim and fire actions.
+pub struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Aim>()
+            .init_resource::<InputMap>()
+            .add_event::<FireEvent>()
+            .configure_set(PlayerInputSet
+                               .in_set(OnUpdate(GameState::Playing))
+                               .run_if(not(resource_exists::<ReplayPlayer>())))
+            .add_startup_system(setup_reticle)
+            .add_systems((mouse_aim, action_aim, stick_aim, fire_input, switch_weapon)
+                             .before(fire)
+                             .in_set(PlayerInputSet))
+            .add_systems((fire, aim_ship, steer)
+                             .before(spawn_laser)
+                             .in_set(PlayerInputSet))
+            .add_system(move_reticle.after(fire));
+    }
+}
+
+/// Keyboard and gamepad aim in units of the whiteboard per second.
+const AIM_SPEED: f32 = 16.0;
+const STICK_DEAD_ZONE: f32 = 0.15;
+
+fn aim_plane_center() -> Vec3 {
+    WHITEBOARD_POSITION + Vec3::new(0.0, 0.0, WHITEBOARD_SIZE.z / 2.0)
+}
+
+impl Aim {
+    /// Moves the target along the whiteboard, never off its edges.
+    pub fn move_by(&mut self, offset: Vec2) {
+        let half = WHITEBOARD_SIZE.truncate() / 2.0;
+        let center = aim_plane_center();
+        let target = (self.target.truncate() + offset).clamp(center.truncate() - half, center.truncate() + half);
+        self.target = target.extend(center.z);
+    }
+}
+
+fn cursor_target(query: &Query<&RaycastSource<MyRaycastSet>>) -> Option<Vec3> {
+    query.iter()
+        .find_map(|pick_source| pick_source.get_nearest_intersection())
+        .map(|(_, intersection)| intersection.position())
+}
+
+fn mouse_aim(
+    mut cursor: EventReader<CursorMoved>,
+    mut aim: ResMut<Aim>,
+    query: Query<&RaycastSource<MyRaycastSet>>
+) {
+    if cursor.iter().last().is_none() {
+        return;
+    }
+    if let Some(target) = cursor_target(&query) {
+        aim.scheme = ControlScheme::Mouse;
+        aim.target = target;
+    }
+}
+
+fn action_aim(
+    actions: Actions,
+    time: Res<Time>,
+    mut aim: ResMut<Aim>,
+) {
+    let mut direction = Vec2::ZERO;
+    let mut scheme = None;
+    for (action, step) in [(Action::AimLeft, Vec2::NEG_X), (Action::AimRight, Vec2::X),
+                           (Action::AimUp, Vec2::Y), (Action::AimDown, Vec2::NEG_Y)] {
+        if let Some(by) = actions.pressed_by(action) {
+            direction += step;
+            scheme = Some(by);
+        }
+    }
+    if let Some(scheme) = scheme {
+        aim.scheme = scheme;
+        aim.move_by(direction * AIM_SPEED * time.delta_seconds());
+    }
+}
+
+fn stick_aim(
+    actions: Actions,
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    time: Res<Time>,
+    mut aim: ResMut<Aim>,
+) {
+    let (x, y) = match actions.aim_stick() {
+        AimStick::Left => (GamepadAxisType::LeftStickX, GamepadAxisType::LeftStickY),
+        AimStick::Right => (GamepadAxisType::RightStickX, GamepadAxisType::RightStickY),
+    };
+    for gamepad in gamepads.iter() {
+        let stick = Vec2::new(
+            axes.get(GamepadAxis::new(gamepad, x)).unwrap_or(0.0),
+            axes.get(GamepadAxis::new(gamepad, y)).unwrap_or(0.0),
+        );
+        if stick.length() > STICK_DEAD_ZONE {
+            aim.scheme = ControlScheme::Gamepad;
+            aim.move_by(stick * AIM_SPEED * time.delta_seconds());
+        }
+    }
+}
+
+/// Steers the ship with the move actions and the stick not used for aiming.
+fn steer(
+    actions: Actions,
+    gamepads: Res<Gamepads>,
+    axes: Res<Axis<GamepadAxis>>,
+    flight_mode: Res<FlightMode>,
+    mut steering: ResMut<Steering>,
+    mut recorder: ResMut<ReplayRecorder>,
+) {
+    let mut direction = Vec2::ZERO;
+    for (action, step) in [(Action::MoveLeft, Vec2::NEG_X), (Action::MoveRight, Vec2::X),
+                           (Action::MoveUp, Vec2::Y), (Action::MoveDown, Vec2::NEG_Y)] {
+        if actions.pressed(action) {
+            direction += step;
+        }
+    }
+    let (x, y) = match actions.aim_stick() {
+        AimStick::Left => (GamepadAxisType::RightStickX, GamepadAxisType::RightStickY),
+        AimStick::Right => (GamepadAxisType::LeftStickX, GamepadAxisType::LeftStickY),
+    };
+    for gamepad in gamepads.iter() {
+        let stick = Vec2::new(
+            axes.get(GamepadAxis::new(gamepad, x)).unwrap_or(0.0),
+            axes.get(GamepadAxis::new(gamepad, y)).unwrap_or(0.0),
+        );
+        if stick.length() > STICK_DEAD_ZONE {
+            direction += stick;
+        }
+    }
+    // the replay keeps the steering as flown, whatever the flight mode is when watching
+    let direction = flight_mode.restrict(direction.clamp_length_max(1.0));
+    if direction != steering.0 {
+        steering.0 = direction;
+        recorder.record(ReplayEvent::Steer { steering: direction.to_array() });
+    }
+}
+
+fn fire_input(
+    actions: Actions,
+    state: Res<State<GameState>>,
+    mut held_over: Local<bool>,
+    mut aim: ResMut<Aim>,
+    mut fire: EventWriter<FireEvent>,
+    query: Query<&RaycastSource<MyRaycastSet>>
+) {
+    // a click or key that started the run does not fire until it is let go
+    if state.is_changed() {
+        *held_over = actions.pressed(Action::Fire);
+    }
+    let Some(scheme) = actions.pressed_by(Action::Fire) else {
+        *held_over = false;
+        return;
+    };
+    if *held_over {
+        return;
+    }
+    // the mouse fires at the cursor, not at where keys or sticks aimed before
+    if scheme == ControlScheme::Mouse {
+        match cursor_target(&query) {
+            Some(target) => aim.target = target,
+            None => return,
+        }
+    }
+    aim.scheme = scheme;
+    fire.send(FireEvent);
+}
+
+/// Shoots while the trigger is held and the weapon is ready, records the shot
+/// for the replay and hands it to the ship.
+fn fire(
+    aim: Res<Aim>,
+    weapon: Option<Res<Weapon>>,
+    arsenal: Option<Res<Arsenal>>,
+    mut fire: EventReader<FireEvent>,
+    mut recorder: ResMut<ReplayRecorder>,
+    mut event_create_laser: EventWriter<CreateLaserEvent>,
+) {
+    if fire.iter().count() == 0 || weapon.is_some_and(|weapon| !weapon.ready()) {
+        return;
+    }
+    let weapon = arsenal.map_or(SingleLaser.name(), |arsenal| arsenal.current().name()).to_string();
+    recorder.record(ReplayEvent::Fire { target: aim.target.to_array(), weapon: weapon.clone() });
+    event_create_laser.send(CreateLaserEvent { target: aim.target, weapon });
+}
+
+fn switch_weapon(
+    actions: Actions,
+    mut arsenal: ResMut<Arsenal>,
+) {
+    if actions.just_pressed(Action::SwitchWeapon) {
+        arsenal.select_next();
+    }
+}
+
+/// Keeps the ship turning towards the aim every frame, not only on shots.
+fn aim_ship(
+    aim: Res<Aim>,
+    mut query: Query<&mut Ship>,
+) {
+    for mut ship in query.iter_mut() {
+        ship.target = aim.target;
+    }
+}
+
+fn setup_reticle(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    aim: Res<Aim>,
+) {
+    let material = materials.add(StandardMaterial {
+        base_color: Color::LIME_GREEN,
+        emissive: Color::LIME_GREEN,
+        unlit: true,
+        ..Default::default()
+    });
+    // the ring lies in its local x/z plane, turned upright to face the camera
+    let across = meshes.add(Mesh::from(shape::Box::new(0.4, 0.04, 0.08)));
+    let upright = meshes.add(Mesh::from(shape::Box::new(0.08, 0.04, 0.4)));
+    let ticks = [
+        (across.clone(), Vec3::new(0.8, 0.0, 0.0)),
+        (across, Vec3::new(-0.8, 0.0, 0.0)),
+        (upright.clone(), Vec3::new(0.0, 0.0, 0.8)),
+        (upright, Vec3::new(0.0, 0.0, -0.8)),
+    ];
+    commands.spawn(PbrBundle {
+        mesh: meshes.add(Mesh::from(shape::Torus {
+            radius: 0.6,
+            ring_radius: 0.08,
+            ..Default::default()
+        })),
+        material: material.clone(),
+        transform: Transform::from_translation(aim.target)
+            .with_rotation(Quat::from_rotation_x(std::f32::consts::FRAC_PI_2)),
+        visibility: Visibility::Hidden,
+        ..Default::default()
+    })
+        .insert(Reticle)
+        .with_children(|parent| {
+            for (mesh, position) in ticks {
+                parent.spawn(PbrBundle {
+                    mesh,
+                    material: material.clone(),
+                    transform: Transform::from_translation(position),
+                    ..Default::default()
+                });
+            }
+        });
+}
+
+/// Crosshair on the whiteboard where the player aims, whatever the device.
+fn move_reticle(
+    aim: Res<Aim>,
+    state: Res<State<GameState>>,
+    player: Option<Res<ReplayPlayer>>,
+    mut query: Query<(&mut Transform, &mut Visibility), With<Reticle>>,
+) {
+    for (mut transform, mut visibility) in query.iter_mut() {
+        transform.translation = aim.target + Vec3::new(0.0, 0.0, 0.05);
+        // a replay only knows the shots, not where the player aimed in between
+        *visibility = if state.0 == GameState::Playing && player.is_none() {
+            Visibility::Visible
+        } else {
+            Visibility::Hidden
+        };
+    }
+}
@@ -0,0 +1,168 @@
+use std::collections::HashSet;
+use std::fmt;
+use std::path::Path;
+use bevy::prelude::*;
+use bevy::asset::{AssetLoader, BoxedFuture, LoadContext, LoadedAsset};
+use bevy::reflect::TypeUuid;
+
+use crate::card::{ParseCardError, PlayingCard, Rank, Suit};
+
+pub const DEFAULT_DECK: &str = "cards/_cards.csv";
+
+/// The cards a talon is built from, loaded from a deck definition file.
+#[derive(Debug, TypeUuid)]
+#[uuid = "edf4fffd-30ce-490d-8b68-fa68e5ddbbf2"]
+pub struct Deck {
+    pub cards: Vec<PlayingCard>
+}
+
+impl Deck {
+    /// All 52 cards without jokers, the same as the shipped deck file.
+    pub fn standard() -> Self {
+        Self {
+            cards: Suit::ALL.into_iter()
+                .flat_map(|suit| Rank::ALL.into_iter().map(move |rank| PlayingCard::new(suit, rank)))
+                .collect()
+        }
+    }
+}
+
+#[derive(Resource)]
+pub struct DeckHandle(pub Handle<Deck>);
+
+#[derive(Debug)]
+pub enum DeckError {
+    Empty,
+    UnknownCard { line: usize, error: ParseCardError },
+    DuplicateCard { line: usize, card: PlayingCard },
+    MissingTexture { line: usize, card: PlayingCard },
+}
+
+impl fmt::Display for DeckError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DeckError::Empty => write!(f, "deck contains no cards"),
+            DeckError::UnknownCard { line, error } => write!(f, "line {}: {}", line, error),
+            DeckError::DuplicateCard { line, card } => write!(f, "line {}: duplicate card '{}'", line, card),
+            DeckError::MissingTexture { line, card } => write!(f, "line {}: no texture for card '{}'", line, card),
+        }
+    }
+}
+
+impl std::error::Error for DeckError {}
+
+/// Parses a deck definition: one card texture name per line or comma separated,
+/// empty lines and lines starting with `#` are ignored.
+pub fn parse_deck(text: &str) -> Result<Vec<(usize, PlayingCard)>, DeckError> {
+    let mut seen = HashSet::new();
+    let mut cards = vec![];
+    for (index, line) in text.lines().enumerate() {
+        let line_number = index + 1;
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        for name in line.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let card: PlayingCard = name
+                .parse()
+                .map_err(|error| DeckError::UnknownCard { line: line_number, error })?;
+            if !seen.insert(card) {
+                return Err(DeckError::DuplicateCard { line: line_number, card });
+            }
+            cards.push((line_number, card));
+        }
+    }
+    if cards.is_empty() {
+        return Err(DeckError::Empty);
+    }
+    Ok(cards)
+}
+
+#[derive(Default)]
+pub struct DeckLoader;
+
+impl AssetLoader for DeckLoader {
+    fn load<'a>(
+        &'a self,
+        bytes: &'a [u8],
+        load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<(), bevy::asset::Error>> {
+        Box::pin(async move {
+            let text = std::str::from_utf8(bytes)?;
+            let cards = parse_deck(text)?;
+            // card textures live in the root `cards` folder, whatever the deck location
+            for (line, card) in cards.iter() {
+                let texture = card.texture_path();
+                if load_context.read_asset_bytes(Path::new(&texture)).await.is_err() {
+                    return Err(DeckError::MissingTexture { line: *line, card: *card }.into());
+                }
+            }
+            let deck = Deck {
+                cards: cards.into_iter().map(|(_, card)| card).collect()
+            };
+            load_context.set_default_asset(LoadedAsset::new(deck));
+            Ok(())
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["csv"]
+    }
+}
+
+pub struct DeckPlugin;
+
+impl Plugin for DeckPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_asset::<Deck>()
+            .init_asset_loader::<DeckLoader>();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::card::CardColor;
+
+    #[test]
+    fn unknown_card_names_its_line() {
+        let err = parse_deck("card_hearts_02\ncard_hearts_1\n").unwrap_err();
+        assert!(matches!(err, DeckError::UnknownCard { line: 2, error: ParseCardError::UnknownRank(_) }), "{}", err);
+        let err = parse_deck("card_stars_02").unwrap_err();
+        assert!(matches!(err, DeckError::UnknownCard { line: 1, error: ParseCardError::UnknownSuit(_) }), "{}", err);
+    }
+
+    #[test]
+    fn duplicate_card_names_its_line() {
+        let err = parse_deck("card_hearts_02, card_spades_K\n\ncard_spades_K\n").unwrap_err();
+        match err {
+            DeckError::DuplicateCard { line, card } => {
+                assert_eq!(line, 3);
+                assert_eq!(card, PlayingCard::new(Suit::Spades, Rank::King));
+            }
+            other => panic!("expected a duplicate, got {}", other),
+        }
+    }
+
+    #[test]
+    fn deck_without_cards_is_empty() {
+        assert!(matches!(parse_deck(""), Err(DeckError::Empty)));
+        assert!(matches!(parse_deck("# no cards\n\n  \n"), Err(DeckError::Empty)));
+    }
+
+    #[test]
+    fn blank_lines_and_crlf_are_skipped() {
+        let cards = parse_deck("card_hearts_A\r\n\r\n# comment\r\ncard_joker_red\r\n").unwrap();
+        assert_eq!(cards, vec![(1, PlayingCard::new(Suit::Hearts, Rank::Ace)),
+                               (4, PlayingCard::Joker(CardColor::Red))]);
+    }
+
+    #[test]
+    fn texture_names_parse_back_for_every_card() {
+        let deck = Deck::standard();
+        assert_eq!(deck.cards.len(), 52);
+        for card in deck.cards {
+            assert_eq!(card.texture_name().parse::<PlayingCard>(), Ok(card));
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use std::fmt;
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::storage;
+
+const CUSTOM_DIFFICULTY_KEY: &str = "difficulty_custom";
+
+/// How hard a run is, picked on the title screen.
+#[derive(Resource, Debug, Clone, Copy, Default, Eq, PartialEq, Hash, Serialize, Deserialize)]
+pub enum Difficulty {
+    Easy,
+    #[default]
+    Normal,
+    Hard,
+    Custom,
+}
+
+impl Difficulty {
+    pub const ALL: [Difficulty; 4] = [Difficulty::Easy, Difficulty::Normal, Difficulty::Hard, Difficulty::Custom];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Easy => "easy",
+            Difficulty::Normal => "normal",
+            Difficulty::Hard => "hard",
+            Difficulty::Custom => "custom",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|d| d == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn previous(&self) -> Self {
+        let index = Self::ALL.iter().position(|d| d == self).unwrap_or(0);
+        Self::ALL[(index + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    /// The values of a preset, `Custom` takes them from `custom`.
+    pub fn settings(&self, custom: &CustomDifficulty) -> DifficultySettings {
+        match self {
+            Difficulty::Easy => DifficultySettings {
+                spawn_interval: 2.5,
+                gravity_scale: 0.35,
+                max_lasers: 14,
+                card_limit_x: 6.0,
+                ramp: None,
+                powerup_interval: Some(8.0),
+            },
+            Difficulty::Normal => DifficultySettings {
+                ramp: Some(DifficultyRamp::default()),
+                powerup_interval: Some(12.0),
+                ..Default::default()
+            },
+            Difficulty::Hard => DifficultySettings {
+                spawn_interval: 1.4,
+                gravity_scale: 0.75,
+                max_lasers: 6,
+                card_limit_x: 11.0,
+                ramp: Some(DifficultyRamp {
+                    spawn_interval_factor: 0.5,
+                    gravity_factor: 2.0,
+                    decoy_chance: 0.4,
+                    curve: 1.5,
+                }),
+                powerup_interval: Some(16.0),
+            },
+            Difficulty::Custom => custom.0,
+        }
+    }
+}
+
+impl fmt::Display for Difficulty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Values the difficulty drives, fixed for a run when it starts.
+#[derive(Resource, Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DifficultySettings {
+    /// Seconds between two falling cards.
+    pub spawn_interval: f32,
+    pub gravity_scale: f32,
+    /// Lasers that can be in flight at once.
+    pub max_lasers: usize,
+    /// Cards enter the play field between `-card_limit_x` and `card_limit_x`.
+    pub card_limit_x: f32,
+    /// Escalation while the stack fills up, a run without it plays the same throughout.
+    #[serde(default)]
+    pub ramp: Option<DifficultyRamp>,
+    /// Seconds between two power-up cards, none fall without it.
+    #[serde(default)]
+    pub powerup_interval: Option<f32>,
+}
+
+impl Default for DifficultySettings {
+    fn default() -> Self {
+        Self {
+            spawn_interval: 2.0,
+            gravity_scale: 0.5,
+            max_lasers: 10,
+            card_limit_x: 8.0,
+            ramp: None,
+            powerup_interval: None,
+        }
+    }
+}
+
+impl DifficultySettings {
+    /// Values at a point of the run, `progress` is the share of the stack placed.
+    pub fn escalation(&self, progress: f32) -> Escalation {
+        match &self.ramp {
+            Some(ramp) => {
+                let level = ramp.level(progress);
+                Escalation {
+                    spawn_interval: self.spawn_interval * (1.0 + (ramp.spawn_interval_factor - 1.0) * level),
+                    gravity_scale: self.gravity_scale * (1.0 + (ramp.gravity_factor - 1.0) * level),
+                    decoy_chance: ramp.decoy_chance * level,
+                }
+            }
+            None => Escalation {
+                spawn_interval: self.spawn_interval,
+                gravity_scale: self.gravity_scale,
+                decoy_chance: 0.0,
+            },
+        }
+    }
+}
+
+/// How a run escalates, each value is reached once the stack is complete.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct DifficultyRamp {
+    /// Factor on the spawn interval.
+    pub spawn_interval_factor: f32,
+    /// Factor on the gravity of new cards.
+    pub gravity_factor: f32,
+    /// Chance of a spawn being a decoy.
+    pub decoy_chance: f32,
+    /// Shape of the ramp: 1 is linear, higher values keep the start calm and
+    /// save the escalation for the end.
+    pub curve: f32,
+}
+
+impl Default for DifficultyRamp {
+    fn default() -> Self {
+        Self {
+            spawn_interval_factor: 0.7,
+            gravity_factor: 1.5,
+            decoy_chance: 0.2,
+            curve: 2.0,
+        }
+    }
+}
+
+impl DifficultyRamp {
+    /// Escalation level from 0 to 1.
+    pub fn level(&self, progress: f32) -> f32 {
+        progress.clamp(0.0, 1.0).powf(self.curve.max(0.0))
+    }
+}
+
+/// Current values of a run, updated as the stack fills up.
+#[derive(Resource, Debug, Clone, Copy, PartialEq)]
+pub struct Escalation {
+    pub spawn_interval: f32,
+    pub gravity_scale: f32,
+    pub decoy_chance: f32,
+}
+
+/// The values of the custom difficulty, read from the save folder.
+#[derive(Resource, Debug, Clone, Copy, Default, Serialize, Deserialize)]
+pub struct CustomDifficulty(pub DifficultySettings);
+
+impl CustomDifficulty {
+    pub fn load() -> Self {
+        storage::load(CUSTOM_DIFFICULTY_KEY)
+            .and_then(|text| match serde_json::from_str(&text) {
+                Ok(custom) => Some(custom),
+                Err(err) => {
+                    warn!("ignoring broken custom difficulty: {}", err);
+                    None
+                }
+            })
+            .unwrap_or_default()
+    }
+}
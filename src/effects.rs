@@ -0,0 +1,268 @@
+use bevy::prelude::*;
+use rand::Rng;
+
+use crate::card::{CardColor, PlayingCard};
+use crate::game::{EndRun, FrameTime, GameRng, GameState};
+use crate::solitaire::collision;
+use crate::world::{self, CAMERA_POSITION};
+
+/// How the sparks of a burst fly.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum Burst {
+    /// Sparks jumping up and falling down.
+    Sparks,
+    /// Slow sparks floating upwards, for a right card.
+    Sparkle,
+    /// Fast splinters flying apart, for a wrong card.
+    Shatter,
+}
+
+impl Burst {
+    /// Sideways spread, upwards speed, share of the gravity and lifetime of its sparks.
+    fn motion(&self) -> (f32, f32, f32, f32) {
+        match self {
+            Burst::Sparks => (1.0, 10.0, 1.0, 2.0),
+            Burst::Sparkle => (0.4, 4.0, 0.3, 1.2),
+            Burst::Shatter => (1.8, 6.0, 1.5, 0.8),
+        }
+    }
+}
+
+/// Bursts a cloud of sparks at the given position.
+pub struct CreateEffectEvent {
+    pub position: Vec3,
+    pub color: Color,
+    pub burst: Burst,
+}
+
+/// What a shot on a card did to the stack.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum HitOutcome {
+    Correct,
+    Wrong,
+    /// Wrong, but a shield took the mistake.
+    Shielded,
+}
+
+/// A card was shot, the effects show whether it was right.
+pub struct HitEvent {
+    pub position: Vec3,
+    pub outcome: HitOutcome,
+}
+
+/// Shakes the camera, fading out by itself.
+#[derive(Resource, Default)]
+pub struct ScreenShake {
+    /// From 0 to 1.
+    pub strength: f32,
+}
+
+impl ScreenShake {
+    pub fn add(&mut self, strength: f32) {
+        self.strength = (self.strength + strength).min(1.0);
+    }
+}
+
+/// Color of the sparks of a hit card, after its suit.
+pub fn card_effect_color(card: &PlayingCard) -> Color {
+    match card.color() {
+        CardColor::Red => Color::rgb(1.0, 0.2, 0.2),
+        CardColor::Black => Color::rgb(0.6, 0.8, 1.0),
+    }
+}
+
+/// A spark, moved by [`update_particles`] without a physics body.
+#[derive(Component)]
+struct Particle {
+    velocity: Vec3,
+    gravity: f32,
+    /// Seconds until it goes back to the pool.
+    life: f32,
+}
+
+/// The spark mesh, one material per spark color, and the hidden sparks waiting to be reused.
+#[derive(Resource, Default)]
+struct ParticlePool {
+    mesh: Handle<Mesh>,
+    materials: Vec<(Color, Handle<StandardMaterial>)>,
+    free: Vec<Entity>,
+    /// Sparks spawned so far, flying or in the pool.
+    count: usize,
+}
+
+impl ParticlePool {
+    fn material(&mut self, color: Color, materials: &mut Assets<StandardMaterial>) -> Handle<StandardMaterial> {
+        if let Some((_, handle)) = self.materials.iter().find(|(c, _)| *c == color) {
+            return handle.clone();
+        }
+        let handle = materials.add(StandardMaterial {
+            metallic: 0.5,
+            emissive: color,
+            ..Default::default()
+        });
+        self.materials.push((color, handle.clone()));
+        handle
+    }
+}
+
+/// Sparks flying off hit cards, green for a right card and red with a shaking screen for a wrong one.
+pub struct EffectsPlugin;
+
+impl Plugin for EffectsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CreateEffectEvent>()
+            .add_event::<HitEvent>()
+            .init_resource::<ParticlePool>()
+            .init_resource::<ScreenShake>()
+            .add_startup_system(setup_particles)
+            .add_systems((hit_feedback, create_effect, update_particles)
+                             .chain()
+                             .after(collision)
+                             .in_set(OnUpdate(GameState::Playing)))
+            .add_system(shake_camera)
+            .add_system(clear_effects.in_schedule(EndRun));
+    }
+}
+
+const EFFECT_SIZE:f32=0.1;
+/// Upper bound of sparks alive at once, bursts beyond it are thinned out.
+const MAX_PARTICLES: usize = 512;
+const PARTICLE_GRAVITY: f32 = 9.81;
+/// Speed of a spark for a random draw of 0.01, the impulse the physics version pushed it with.
+const PARTICLE_SPEED: f32 = 1000.0;
+const CORRECT_COLOR: Color = Color::rgb(0.3, 1.0, 0.4);
+const WRONG_COLOR: Color = Color::rgb(1.0, 0.15, 0.1);
+const MISTAKE_SHAKE: f32 = 0.6;
+/// Share of a full shake fading per second.
+const SHAKE_FADE: f32 = 2.0;
+/// Camera offset at full shake.
+const MAX_SHAKE_OFFSET: f32 = 0.3;
+
+fn setup_particles(
+    mut pool: ResMut<ParticlePool>,
+    mut meshes: ResMut<Assets<Mesh>>,
+) {
+    pool.mesh = meshes.add(Mesh::from(shape::Box::new(EFFECT_SIZE, EFFECT_SIZE, EFFECT_SIZE)));
+}
+
+fn hit_feedback(
+    mut events: EventReader<HitEvent>,
+    mut shake: ResMut<ScreenShake>,
+    mut event_create_effect: EventWriter<CreateEffectEvent>,
+) {
+    for HitEvent { position, outcome } in events.iter() {
+        let (color, burst) = match outcome {
+            HitOutcome::Correct => (CORRECT_COLOR, Burst::Sparkle),
+            HitOutcome::Wrong => {
+                shake.add(MISTAKE_SHAKE);
+                (WRONG_COLOR, Burst::Shatter)
+            }
+            HitOutcome::Shielded => (Color::SILVER, Burst::Shatter),
+        };
+        event_create_effect.send(CreateEffectEvent { position: *position, color, burst });
+    }
+}
+
+fn create_effect(
+    mut commands: Commands,
+    mut event_create_effect: EventReader<CreateEffectEvent>,
+    mut game_rng: ResMut<GameRng>,
+    mut pool: ResMut<ParticlePool>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut query: Query<(&mut Particle, &mut Transform, &mut Handle<StandardMaterial>, &mut Visibility)>,
+)
+{
+    let rng = &mut game_rng.effects;
+    for CreateEffectEvent { position, color, burst } in event_create_effect.iter() {
+        let material = pool.material(*color, &mut materials);
+        let (spread, lift, gravity, life) = burst.motion();
+        for x in -2..2 {
+            for y in 0..2 {
+                for z in -2..2 {
+                    let drift: (f32, f32) = (rng.gen_range(-0.01..0.01), rng.gen_range(-0.01..0.01));
+                    let particle = Particle {
+                        velocity: Vec3::new(drift.0 * spread * PARTICLE_SPEED, lift, drift.1 * spread * PARTICLE_SPEED),
+                        gravity: gravity * PARTICLE_GRAVITY,
+                        life,
+                    };
+                    let transform = Transform::from_translation(
+                        *position + Vec3::new(x as f32, y as f32, z as f32) * EFFECT_SIZE);
+                    if let Some(entity) = pool.free.pop() {
+                        if let Ok((mut reused, mut reused_transform, mut reused_material, mut visibility))
+                            = query.get_mut(entity) {
+                            *reused = particle;
+                            *reused_transform = transform;
+                            *reused_material = material.clone();
+                            *visibility = Visibility::Inherited;
+                        }
+                    } else if pool.count < MAX_PARTICLES {
+                        pool.count += 1;
+                        commands.spawn(PbrBundle {
+                            mesh: pool.mesh.clone(),
+                            material: material.clone(),
+                            transform,
+                            ..Default::default()
+                        })
+                            .insert(particle);
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Lets the sparks fall and hides the burnt out ones for the next burst.
+fn update_particles(
+    frame_time: Res<FrameTime>,
+    mut pool: ResMut<ParticlePool>,
+    mut query: Query<(Entity, &mut Particle, &mut Transform, &mut Visibility)>,
+)
+{
+    let dt = frame_time.world_delta.as_secs_f32();
+    for (entity, mut particle, mut transform, mut visibility) in query.iter_mut() {
+        if *visibility == Visibility::Hidden {
+            continue;
+        }
+        particle.life -= dt;
+        if particle.life <= 0.0 {
+            *visibility = Visibility::Hidden;
+            pool.free.push(entity);
+            continue;
+        }
+        particle.velocity.y -= particle.gravity * dt;
+        transform.translation += particle.velocity * dt;
+    }
+}
+
+/// Moves the camera around its place while the [`ScreenShake`] fades.
+fn shake_camera(
+    time: Res<Time>,
+    mut shake: ResMut<ScreenShake>,
+    mut query: Query<&mut Transform, With<world::Camera>>,
+) {
+    if shake.strength <= 0.0 {
+        return;
+    }
+    shake.strength = (shake.strength - SHAKE_FADE * time.delta_seconds()).max(0.0);
+    // squared, so small shakes stay subtle; no random numbers, replays keep their sequence
+    let t = time.elapsed_seconds();
+    let offset = Vec3::new((t * 61.0).sin(), (t * 53.0).cos(), 0.0) * shake.strength.powi(2) * MAX_SHAKE_OFFSET;
+    for mut transform in query.iter_mut() {
+        transform.translation = CAMERA_POSITION + offset;
+    }
+}
+
+fn clear_effects(
+    mut pool: ResMut<ParticlePool>,
+    mut shake: ResMut<ScreenShake>,
+    mut query: Query<(Entity, &mut Visibility), With<Particle>>
+) {
+    // the last shake frame puts the camera back in place
+    shake.strength = shake.strength.min(f32::EPSILON);
+    for (entity, mut visibility) in query.iter_mut() {
+        if *visibility != Visibility::Hidden {
+            *visibility = Visibility::Hidden;
+            pool.free.push(entity);
+        }
+    }
+}
@@ -0,0 +1,315 @@
+use std::time::Duration;
+use bevy::prelude::*;
+use bevy::ecs::schedule::ScheduleLabel;
+use bevy::time::Stopwatch;
+use bevy_rapier3d::prelude::*;
+use rand::SeedableRng;
+use rand::rngs::StdRng;
+
+use crate::audio::AudioSettings;
+use crate::controls::InputMap;
+use crate::difficulty::{CustomDifficulty, Difficulty, DifficultySettings};
+use crate::highscore::{self, HighScore, HighScores};
+use crate::replay::{Replay, ReplayPlayer, ReplayRecorder};
+use crate::rules::{Rules, RulesPlugin};
+use crate::ship::FlightMode;
+
+#[derive(Debug, Clone, Default, Copy, Eq, PartialEq, Hash, States)]
+pub enum GameState {
+    #[default]
+    GameStart,
+    Playing,
+    GameOver,
+    Controls,
+    Paused,
+}
+
+/// Present from the start of a run until it is torn down, also while paused.
+#[derive(Resource)]
+pub struct ActiveRun;
+
+/// Tears a run down: after the game over screen, or when restarting or quitting
+/// from the pause menu. Plugins add the cleanup of their entities and resources here.
+#[derive(ScheduleLabel, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct EndRun;
+
+#[derive(Resource)]
+pub struct Score {
+    pub mistakes:u32,
+    pub time:Stopwatch
+}
+
+impl Default for Score{
+    fn default() -> Self {
+        Self {
+            mistakes:0,
+            time:Stopwatch::new()
+        }
+    }
+}
+
+/// Drives every random decision of a run, so a seed always replays the same card sequence.
+#[derive(Resource)]
+pub struct GameRng {
+    pub seed:u64,
+    fixed:bool,
+    /// Card draws, spawn positions and power-ups.
+    pub rng:StdRng,
+    /// Cosmetic draws, kept apart so the number of effects does not change the cards.
+    pub effects:StdRng
+}
+
+/// Mixed into the seed of the effects stream, so it does not repeat the card stream.
+const EFFECTS_STREAM:u64 = 0x9e37_79b9_7f4a_7c15;
+
+impl Default for GameRng {
+    fn default() -> Self {
+        Self::new(None)
+    }
+}
+
+impl GameRng {
+    pub fn new(seed:Option<u64>) -> Self {
+        let fixed = seed.is_some();
+        let seed = seed.unwrap_or_else(rand::random);
+        Self {
+            seed,
+            fixed,
+            rng:StdRng::seed_from_u64(seed),
+            effects:StdRng::seed_from_u64(seed ^ EFFECTS_STREAM)
+        }
+    }
+
+    fn start_run(&mut self) {
+        if !self.fixed {
+            self.seed = rand::random();
+        }
+        self.reseed();
+    }
+
+    fn start_run_with(&mut self, seed:u64) {
+        self.seed = seed;
+        self.reseed();
+    }
+
+    fn reseed(&mut self) {
+        self.rng = StdRng::seed_from_u64(self.seed);
+        self.effects = StdRng::seed_from_u64(self.seed ^ EFFECTS_STREAM);
+    }
+}
+
+/// Length of the current gameplay frame, taken from the recording during replays.
+#[derive(Resource, Default)]
+pub struct FrameTime {
+    pub delta:Duration,
+    /// `delta` scaled by the [`TimeScale`], for the physics and the card spawns.
+    /// The physics step is capped to 1/60 s on top.
+    pub world_delta:Duration
+}
+
+/// Speed of the game world against the clock, below 1 during slow motion.
+#[derive(Resource)]
+pub struct TimeScale(pub f32);
+
+impl Default for TimeScale {
+    fn default() -> Self {
+        Self(1.0)
+    }
+}
+
+/// Game states, seeding, frame timing and scoring of a run. Needs no window or
+/// renderer, so it also runs under `MinimalPlugins`.
+pub struct GameLogicPlugin;
+
+impl Plugin for GameLogicPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Score>()
+            .init_resource::<HighScores>()
+            .init_resource::<GameRng>()
+            .init_resource::<FrameTime>()
+            .init_resource::<TimeScale>()
+            .init_resource::<Difficulty>()
+            .init_resource::<CustomDifficulty>()
+            .init_resource::<DifficultySettings>()
+            .add_plugin(RulesPlugin)
+            .add_state::<GameState>()
+            .init_schedule(EndRun)
+            .add_system(start_run.run_if(starting_run).in_schedule(OnEnter(GameState::Playing)))
+            .add_system(advance_frame
+                            .before(OnUpdate(GameState::Playing))
+                            .run_if(in_state(GameState::Playing)))
+            .add_system(tick_clock.in_set(OnUpdate(GameState::Playing)))
+            .add_system(record_highscore.in_schedule(OnEnter(GameState::GameOver)))
+            .add_system(end_run.in_schedule(OnExit(GameState::GameOver)))
+            .add_system(cleanup_run.in_schedule(EndRun));
+    }
+}
+
+/// Keeps the high score table, the last replay, the controls and the flight mode
+/// between sessions.
+pub struct PersistencePlugin;
+
+impl Plugin for PersistencePlugin {
+    fn build(&self, app: &mut App) {
+        app.insert_resource(HighScores::load())
+            .insert_resource(InputMap::load())
+            .insert_resource(CustomDifficulty::load())
+            .insert_resource(FlightMode::load())
+            .insert_resource(AudioSettings::load())
+            .add_system(save_run
+                            .after(record_highscore)
+                            .in_schedule(OnEnter(GameState::GameOver)))
+            .add_system(save_controls.in_schedule(OnExit(GameState::Controls)))
+            .add_systems((save_flight_mode, save_audio).in_schedule(OnExit(GameState::GameStart)));
+    }
+}
+
+/// Run condition of the systems setting up a new run on `OnEnter(GameState::Playing)`,
+/// false when play resumes from the pause menu.
+pub fn starting_run(run: Option<Res<ActiveRun>>) -> bool {
+    run.is_none()
+}
+
+/// Runs the [`EndRun`] schedule.
+pub fn end_run(world: &mut World) {
+    world.run_schedule(EndRun);
+}
+
+/// Seeds the run, fixes its difficulty settings and starts recording it, unless
+/// a replay is being watched.
+pub fn start_run(
+    mut commands: Commands,
+    mut score: ResMut<Score>,
+    mut game_rng: ResMut<GameRng>,
+    mut rules: ResMut<Rules>,
+    mut difficulty: ResMut<Difficulty>,
+    custom: Res<CustomDifficulty>,
+    mut settings: ResMut<DifficultySettings>,
+    player: Option<Res<ReplayPlayer>>,
+) {
+    match player {
+        Some(player) => {
+            game_rng.start_run_with(player.replay.seed);
+            if !rules.select_by_name(&player.replay.rules) {
+                warn!("replay uses unknown rules '{}'", player.replay.rules);
+            }
+            *difficulty = player.replay.difficulty;
+            *settings = player.replay.settings;
+        }
+        None => {
+            game_rng.start_run();
+            *settings = difficulty.settings(&custom);
+            commands.insert_resource(ReplayRecorder {
+                replay: Replay::new(game_rng.seed, rules.current().name(), *difficulty, *settings)
+            });
+        }
+    }
+
+    commands.insert_resource(ActiveRun);
+    score.time.reset();
+    score.mistakes = 0;
+}
+
+/// Longest physics step of a frame. On slower frames the cards fall less than the
+/// clock runs, which a replay repeats, as it caps the recorded deltas the same way.
+const MAX_PHYSICS_DT:f32 = 1.0/60.0;
+
+fn advance_frame(
+    time:Res<Time>,
+    time_scale: Res<TimeScale>,
+    mut frame_time: ResMut<FrameTime>,
+    rapier_config: Option<ResMut<RapierConfiguration>>,
+    recorder: Option<ResMut<ReplayRecorder>>,
+    player: Option<ResMut<ReplayPlayer>>,
+    mut state: ResMut<NextState<GameState>>,
+) {
+    let delta = match player {
+        Some(mut player) => match player.next_frame() {
+            Some(delta) => delta,
+            None => {
+                state.set(GameState::GameOver);
+                return;
+            }
+        },
+        None => time.delta_seconds(),
+    };
+    frame_time.delta = Duration::from_secs_f32(delta);
+    frame_time.world_delta = frame_time.delta.mul_f32(time_scale.0);
+    if let Some(mut recorder) = recorder {
+        recorder.replay.frames.push(delta);
+    }
+    // a fixed step per frame makes the physics repeat exactly in a replay
+    if let Some(mut rapier_config) = rapier_config {
+        rapier_config.timestep_mode = TimestepMode::Fixed {
+            dt: delta.min(MAX_PHYSICS_DT) * time_scale.0,
+            substeps: 1
+        };
+    }
+}
+
+fn tick_clock(
+    mut score: ResMut<Score>,
+    frame_time:Res<FrameTime>,
+) {
+    score.time.tick(frame_time.delta);
+}
+
+pub fn record_highscore(
+    mut commands: Commands,
+    score: Res<Score>,
+    game_rng: Res<GameRng>,
+    rules: Res<Rules>,
+    difficulty: Res<Difficulty>,
+    mut highscores: ResMut<HighScores>,
+    recorder: Option<Res<ReplayRecorder>>,
+) {
+    // a watched replay is not a new run
+    if recorder.is_some() {
+        highscores.insert(HighScore {
+            time: score.time.elapsed_secs(),
+            date: highscore::today(),
+            seed: game_rng.seed,
+            rules: rules.current().name().to_string(),
+            mistakes: score.mistakes,
+            difficulty: *difficulty,
+        });
+    }
+    commands.remove_resource::<ReplayPlayer>();
+}
+
+fn save_run(
+    highscores: Res<HighScores>,
+    recorder: Option<Res<ReplayRecorder>>,
+) {
+    if let Some(recorder) = recorder {
+        recorder.replay.save_last();
+        highscores.save();
+    }
+}
+
+fn save_controls(
+    input_map: Res<InputMap>,
+) {
+    input_map.save();
+}
+
+fn save_flight_mode(
+    flight_mode: Res<FlightMode>,
+) {
+    flight_mode.save();
+}
+
+fn save_audio(
+    settings: Res<AudioSettings>,
+) {
+    settings.save();
+}
+
+fn cleanup_run(
+    mut commands: Commands,
+    mut time_scale: ResMut<TimeScale>,
+) {
+    time_scale.0 = 1.0;
+    commands.remove_resource::<ReplayRecorder>();
+    commands.remove_resource::<ActiveRun>();
+}
@@ -0,0 +1,157 @@
+//! Runs the game logic without window or renderer, for tests and tools.
+
+use std::time::Duration;
+use bevy::input::ButtonState;
+use bevy::input::InputPlugin;
+use bevy::input::keyboard::KeyboardInput;
+use bevy::prelude::*;
+use bevy::time::TimeUpdateStrategy;
+use bevy::utils::Instant;
+use bevy_rapier3d::prelude::{CollisionEvent, RigidBody};
+use bevy_rapier3d::rapier::geometry::CollisionEventFlags;
+
+use crate::card::PlayingCard;
+use crate::controls::{Action, Binding, InputMap};
+use crate::deck::{Deck, DeckHandle};
+use crate::difficulty::Difficulty;
+use crate::effects::EffectsPlugin;
+use crate::game::{GameLogicPlugin, GameRng, GameState, Score};
+use crate::menus::MenusPlugin;
+use crate::powerup::{PowerUp, PowerUpCard, PowerUpPlugin};
+use crate::rules::{Rules, Stack};
+use crate::ship::Laser;
+use crate::solitaire::{Card, SolitairePlugin, SpawnTimer, Talon};
+
+/// Length of every frame of a [`HeadlessGame`].
+pub const FRAME: Duration = Duration::from_nanos(16_666_667);
+
+pub struct HeadlessGame {
+    pub app: App
+}
+
+impl HeadlessGame {
+    /// A game on the start screen with the standard deck and a fixed seed.
+    /// The clock moves by [`FRAME`] per step, whatever time passes outside.
+    pub fn new(seed: u64) -> Self {
+        let mut app = App::new();
+        app.add_plugins(MinimalPlugins)
+            .insert_resource(TimeUpdateStrategy::ManualInstant(Instant::now()))
+            .add_plugin(AssetPlugin::default())
+            .add_plugin(InputPlugin)
+            .add_asset::<Mesh>()
+            .add_asset::<StandardMaterial>()
+            .insert_resource(GameRng::new(Some(seed)))
+            .add_plugin(GameLogicPlugin)
+            .add_plugin(SolitairePlugin::default())
+            .add_plugin(PowerUpPlugin)
+            .add_plugin(EffectsPlugin)
+            .add_plugin(MenusPlugin)
+            .init_resource::<InputMap>();
+        let deck = app.world.resource_mut::<Assets<Deck>>().add(Deck::standard());
+        app.insert_resource(DeckHandle(deck));
+        app.update();
+        Self {
+            app
+        }
+    }
+
+    pub fn select_rules(&mut self, name: &str) -> bool {
+        self.app.world.resource_mut::<Rules>().select_by_name(name)
+    }
+
+    pub fn select_difficulty(&mut self, difficulty: Difficulty) {
+        *self.app.world.resource_mut::<Difficulty>() = difficulty;
+    }
+
+    /// Leaves the start screen and steps into the first frame of a run.
+    /// Cards only fall when spawned by the test, the spawn timer is paused.
+    pub fn start(&mut self) {
+        self.app.world.resource_mut::<NextState<GameState>>().set(GameState::Playing);
+        self.step();
+        self.app.world.resource_mut::<SpawnTimer>().0.pause();
+    }
+
+    pub fn pause(&mut self) {
+        self.app.world.resource_mut::<NextState<GameState>>().set(GameState::Paused);
+        self.step();
+    }
+
+    pub fn resume(&mut self) {
+        self.app.world.resource_mut::<NextState<GameState>>().set(GameState::Playing);
+        self.step();
+    }
+
+    pub fn step(&mut self) {
+        if let TimeUpdateStrategy::ManualInstant(now) = self.app.world.resource_mut::<TimeUpdateStrategy>().as_mut() {
+            *now += FRAME;
+        }
+        self.app.update();
+    }
+
+    /// Presses the first key bound to the action for one frame.
+    pub fn press(&mut self, action: Action) {
+        let key = self.app.world.resource::<InputMap>().bindings(action).iter()
+            .find_map(|binding| match binding {
+                Binding::Key(key) => Some(*key),
+                _ => None,
+            })
+            .expect("the action has a key");
+        let event = |state| KeyboardInput { scan_code: 0, key_code: Some(key), state };
+        self.app.world.send_event(event(ButtonState::Pressed));
+        self.step();
+        self.app.world.send_event(event(ButtonState::Released));
+    }
+
+    /// Puts a card from the talon into the play field.
+    pub fn spawn_card(&mut self, face: PlayingCard) -> Entity {
+        self.app.world.resource_mut::<Talon>().cards.retain(|card| *card != face);
+        self.app.world
+            .spawn((TransformBundle::from_transform(Transform::from_xyz(0.0, 5.0, -24.0)),
+                    RigidBody::Dynamic,
+                    Card{face}))
+            .id()
+    }
+
+    /// Hits a card with a laser, as the physics would report it.
+    pub fn fire_at(&mut self, card: Entity) {
+        let position = self.app.world.get::<Transform>(card).map(|t| t.translation).unwrap_or_default();
+        let laser = self.app.world
+            .spawn((TransformBundle::from_transform(Transform::from_translation(position)), Laser))
+            .id();
+        self.app.world.send_event(CollisionEvent::Started(laser, card, CollisionEventFlags::empty()));
+    }
+
+    /// Spawns a card, shoots it and steps one frame.
+    pub fn hit(&mut self, face: PlayingCard) {
+        let card = self.spawn_card(face);
+        self.fire_at(card);
+        self.step();
+    }
+
+    /// Drops a special card into the play field, shoots it and steps one frame.
+    pub fn collect(&mut self, powerup: PowerUp) {
+        let card = self.app.world
+            .spawn((TransformBundle::from_transform(Transform::from_xyz(0.0, 5.0, -24.0)),
+                    RigidBody::Dynamic,
+                    PowerUpCard(powerup)))
+            .id();
+        self.fire_at(card);
+        self.step();
+    }
+
+    pub fn state(&self) -> GameState {
+        self.app.world.resource::<State<GameState>>().0
+    }
+
+    pub fn score(&self) -> &Score {
+        self.app.world.resource::<Score>()
+    }
+
+    pub fn stack(&self) -> &Stack {
+        self.app.world.resource::<Stack>()
+    }
+
+    pub fn talon(&self) -> &Talon {
+        self.app.world.resource::<Talon>()
+    }
+}
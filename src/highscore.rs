@@ -0,0 +1,105 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::difficulty::Difficulty;
+use crate::storage;
+
+const HIGHSCORE_KEY: &str = "highscores";
+/// Runs kept per table, every rule set has one per difficulty.
+pub const MAX_HIGHSCORES: usize = 10;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HighScore {
+    pub time: f32,
+    pub date: String,
+    pub seed: u64,
+    pub rules: String,
+    pub mistakes: u32,
+    /// Runs saved before difficulties existed were played on normal.
+    #[serde(default)]
+    pub difficulty: Difficulty,
+}
+
+impl HighScore {
+    /// Runs of the same table compete for its places.
+    fn same_table(&self, other: &HighScore) -> bool {
+        self.rules == other.rules && self.difficulty == other.difficulty
+    }
+}
+
+/// Fastest finished runs of all tables, best first.
+#[derive(Resource, Debug, Default, Serialize, Deserialize)]
+pub struct HighScores {
+    pub entries: Vec<HighScore>
+}
+
+impl HighScores {
+    pub fn load() -> Self {
+        storage::load(HIGHSCORE_KEY)
+            .and_then(|text| match serde_json::from_str(&text) {
+                Ok(scores) => Some(scores),
+                Err(err) => {
+                    warn!("ignoring broken high score table: {}", err);
+                    None
+                }
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let result = serde_json::to_string_pretty(self)
+            .map_err(|e| e.to_string())
+            .and_then(|text| storage::save(HIGHSCORE_KEY, &text));
+        if let Err(err) = result {
+            error!("could not save high scores: {}", err);
+        }
+    }
+
+    /// Adds a finished run and returns its place in its table, if it made it.
+    pub fn insert(&mut self, entry: HighScore) -> Option<usize> {
+        let place = self.entries.iter()
+            .filter(|e| e.same_table(&entry))
+            .take_while(|e| e.time <= entry.time)
+            .count();
+        if place >= MAX_HIGHSCORES {
+            return None;
+        }
+        let index = self.entries.iter().position(|e| entry.time < e.time).unwrap_or(self.entries.len());
+        self.entries.insert(index, entry.clone());
+        // the slowest run of the table drops out once it is full
+        let mut kept = 0;
+        self.entries.retain(|e| {
+            if !e.same_table(&entry) {
+                return true;
+            }
+            kept += 1;
+            kept <= MAX_HIGHSCORES
+        });
+        Some(place)
+    }
+
+    /// Best time of the rules on a difficulty, other difficulties are not comparable.
+    pub fn best(&self, rules: &str, difficulty: Difficulty) -> Option<f32> {
+        self.entries.iter().find(|e| e.rules == rules && e.difficulty == difficulty).map(|e| e.time)
+    }
+
+    /// The table of the rules on a difficulty, as text.
+    pub fn table(&self, rules: &str, difficulty: Difficulty) -> String {
+        let mut text = format!(" High scores {} ({}) \n", rules, difficulty);
+        let entries: Vec<_> = self.entries.iter()
+            .filter(|e| e.rules == rules && e.difficulty == difficulty)
+            .collect();
+        if entries.is_empty() {
+            text.push_str(" no runs finished yet \n");
+        }
+        for (place, e) in entries.into_iter().enumerate() {
+            text.push_str(&format!(" {:>2}. {:>6.1}s  {}  seed {}  mistakes {} \n",
+                                   place + 1, e.time, e.date, e.seed, e.mistakes));
+        }
+        text
+    }
+}
+
+pub fn today() -> String {
+    chrono::Local::now().format("%Y-%m-%d").to_string()
+}
@@ -0,0 +1,281 @@
+use bevy::prelude::*;
+
+use crate::difficulty::Difficulty;
+use crate::game::{starting_run, EndRun, GameState, Score};
+use crate::highscore::HighScores;
+use crate::powerup::PowerUps;
+use crate::rules::{Rules, Stack};
+use crate::weapon::{Arsenal, Weapon};
+
+#[derive(Component)]
+pub struct Besttext;
+
+#[derive(Component)]
+pub struct Timetext;
+
+/// Frame of the weapon heat bar.
+#[derive(Component)]
+pub struct HeatGauge;
+
+/// Filled part of the heat bar.
+#[derive(Component)]
+pub struct HeatLevel;
+
+/// Name of the selected weapon, above the heat bar.
+#[derive(Component)]
+pub struct Weapontext;
+
+/// Shields and slow motion the player collected.
+#[derive(Component)]
+pub struct PowerUptext;
+
+/// Hint for the next card of a pile.
+#[derive(Component)]
+pub struct NextCardtext(pub usize);
+
+/// Best time, next cards, the running clock, the weapon heat and active power-ups.
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(setup_playing.run_if(starting_run).in_schedule(OnEnter(GameState::Playing)))
+            .add_system(scoreboard.in_set(OnUpdate(GameState::Playing)))
+            .add_system(heat_gauge.in_set(OnUpdate(GameState::Playing)))
+            .add_system(powerup_status.in_set(OnUpdate(GameState::Playing)))
+            .add_system(clear_hud.in_schedule(EndRun));
+    }
+}
+
+fn setup_playing(
+    mut commands: Commands,
+    rules: Res<Rules>,
+    asset_server: Res<AssetServer>,
+) {
+    // scoreboard
+    commands.spawn(TextBundle {
+        text: Text::from_section(
+            "Best:",
+            TextStyle {
+                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                font_size: 40.0,
+                color: Color::rgb(0.5, 0.5, 1.0),
+            }
+        ),
+        style: Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(5.0),
+                left: Val::Px(5.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+        .insert(Besttext);
+
+    let piles = rules.current().piles();
+    for pile in 0..piles {
+        // a single pile sits next to the time, foundations get a row of their own
+        let position = if piles == 1 {
+            UiRect {
+                top: Val::Px(5.0),
+                right: Val::Px(350.0),
+                ..Default::default()
+            }
+        } else {
+            UiRect {
+                top: Val::Px(50.0),
+                left: Val::Px(5.0 + pile as f32 * 230.0),
+                ..Default::default()
+            }
+        };
+        commands.spawn(TextBundle {
+            text: Text::from_section(
+                "Next Card:",
+                TextStyle {
+                    font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                    font_size: 40.0,
+                    color: Color::rgb(0.5, 0.5, 1.0),
+                }
+            ),
+            style: Style {
+                position_type: PositionType::Absolute,
+                position,
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+            .insert(NextCardtext(pile));
+    }
+
+    commands.spawn(TextBundle {
+        text: Text::from_section(
+            "Time:",
+            TextStyle {
+                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                font_size: 40.0,
+                color: Color::rgb(0.5, 0.5, 1.0),
+            }
+        ),
+        style: Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(5.0),
+                right: Val::Px(25.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+        .insert(Timetext);
+
+    commands.spawn(TextBundle {
+        text: Text::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                font_size: 24.0,
+                color: Color::rgb(0.5, 0.5, 1.0),
+            }
+        ),
+        style: Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                bottom: Val::Px(28.0),
+                left: Val::Px(10.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+        .insert(Weapontext);
+
+    commands.spawn(TextBundle {
+        text: Text::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                font_size: 24.0,
+                color: Color::rgb(0.5, 0.5, 1.0),
+            }
+        ),
+        style: Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                bottom: Val::Px(10.0),
+                right: Val::Px(10.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+        .insert(PowerUptext);
+
+    commands.spawn(NodeBundle {
+        style: Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                bottom: Val::Px(10.0),
+                left: Val::Px(10.0),
+                ..Default::default()
+            },
+            size: Size::new(Val::Px(200.0), Val::Px(14.0)),
+            ..Default::default()
+        },
+        background_color: Color::rgba(0.1, 0.1, 0.3, 0.8).into(),
+        ..Default::default()
+    })
+        .insert(HeatGauge)
+        .with_children(|parent| {
+            parent.spawn(NodeBundle {
+                style: Style {
+                    size: Size::new(Val::Percent(0.0), Val::Percent(100.0)),
+                    ..Default::default()
+                },
+                background_color: HEAT_COLOR.into(),
+                ..Default::default()
+            })
+                .insert(HeatLevel);
+        });
+}
+
+const HEAT_COLOR: Color = Color::rgb(1.0, 0.6, 0.1);
+const OVERHEAT_COLOR: Color = Color::rgb(1.0, 0.1, 0.1);
+
+/// Stays empty without the weapon plugin, the plain laser does not heat up.
+fn heat_gauge(
+    weapon: Option<Res<Weapon>>,
+    arsenal: Option<Res<Arsenal>>,
+    mut query: Query<(&mut Style, &mut BackgroundColor), With<HeatLevel>>,
+    mut text_query: Query<&mut Text, With<Weapontext>>,
+) {
+    let (Some(weapon), Some(arsenal)) = (weapon, arsenal) else {
+        return;
+    };
+    for mut text in text_query.iter_mut() {
+        text.sections[0].value = arsenal.current().name().to_string();
+    }
+    for (mut style, mut color) in query.iter_mut() {
+        style.size.width = Val::Percent(weapon.heat * 100.0);
+        *color = if weapon.is_overheated() { OVERHEAT_COLOR } else { HEAT_COLOR }.into();
+    }
+}
+
+/// Stays empty without the power-up plugin.
+fn powerup_status(
+    powerups: Option<Res<PowerUps>>,
+    mut query: Query<&mut Text, With<PowerUptext>>,
+) {
+    let Some(powerups) = powerups else {
+        return;
+    };
+    let mut status = Vec::new();
+    if powerups.shields > 0 {
+        status.push(format!("shield x{}", powerups.shields));
+    }
+    if powerups.slow_motion > 0.0 {
+        status.push(format!("slow motion {:.0}", powerups.slow_motion.ceil()));
+    }
+    for mut text in query.iter_mut() {
+        text.sections[0].value = status.join("  ");
+    }
+}
+
+fn scoreboard(
+    score: Res<Score>,
+    stack: Res<Stack>,
+    rules: Res<Rules>,
+    difficulty: Res<Difficulty>,
+    highscores: Res<HighScores>,
+    mut best_query: Query<&mut Text, (With<Besttext>, Without<NextCardtext>,Without<Timetext>)>,
+    mut next_query: Query<(&mut Text, &NextCardtext), (Without<Timetext>,Without<Besttext>)>,
+    mut time_query: Query<&mut Text, (With<Timetext>,Without<Besttext>,Without<NextCardtext>)>
+) {
+    let mut best_text = best_query.single_mut();
+    best_text.sections[0].value = match highscores.best(rules.current().name(), *difficulty) {
+        Some(best) => format!("Best: {:.1}", best),
+        None => "Best: -".to_string(),
+    };
+
+    for (mut next_text, pile) in next_query.iter_mut() {
+        next_text.sections[0].value = format!("{}: {}", rules.current().pile_name(pile.0),
+                                              stack.hint(rules.current(), pile.0));
+    }
+
+    let mut time_text = time_query.single_mut();
+    time_text.sections[0].value = format!("Time: {:.1}", score.time.elapsed_secs());
+}
+
+fn clear_hud(
+    mut commands: Commands,
+    query: Query<Entity, Or<(With<Besttext>, With<Timetext>, With<NextCardtext>, With<HeatGauge>, With<Weapontext>,
+                             With<PowerUptext>)>>
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
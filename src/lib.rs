@@ -0,0 +1,53 @@
+use bevy::app::PluginGroupBuilder;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+pub mod assets;
+pub mod audio;
+pub mod card;
+pub mod controls;
+pub mod deck;
+pub mod difficulty;
+pub mod effects;
+pub mod game;
+pub mod harness;
+pub mod highscore;
+pub mod hud;
+pub mod menus;
+pub mod pause;
+pub mod powerup;
+pub mod replay;
+pub mod rules;
+pub mod ship;
+pub mod solitaire;
+pub mod storage;
+pub mod title;
+pub mod tween;
+pub mod weapon;
+pub mod world;
+
+/// Everything of the game. Single subsystems can be left out with
+/// `AirAcePlugins.build().disable::<hud::HudPlugin>()`, or configured with `set`.
+pub struct AirAcePlugins;
+
+impl PluginGroup for AirAcePlugins {
+    fn build(self) -> PluginGroupBuilder {
+        PluginGroupBuilder::start::<Self>()
+            .add(game::GameLogicPlugin)
+            .add(game::PersistencePlugin)
+            .add(RapierPhysicsPlugin::<NoUserData>::default())
+            .add(RapierDebugRenderPlugin::default())
+            .add(world::WorldPlugin)
+            .add(solitaire::SolitairePlugin::default())
+            .add(ship::ShipPlugin::default())
+            .add(weapon::WeaponPlugin)
+            .add(powerup::PowerUpPlugin)
+            .add(controls::ControlsPlugin)
+            .add(effects::EffectsPlugin)
+            .add(audio::SoundPlugin)
+            .add(hud::HudPlugin)
+            .add(title::TitlePlugin)
+            .add(menus::MenusPlugin)
+            .add(pause::PausePlugin)
+    }
+}
@@ -0,0 +1,263 @@
+use bevy::prelude::*;
+
+use crate::controls::{Action, Actions, AimStick, Binding, InputMap};
+use crate::difficulty::Difficulty;
+use crate::game::{record_highscore, GameRng, GameState};
+use crate::highscore::HighScores;
+use crate::replay::{Replay, ReplayPlayer, ReplayRecorder};
+use crate::rules::Rules;
+
+#[derive(Component)]
+struct GameOvertext;
+
+#[derive(Component)]
+struct HighScoretext;
+
+#[derive(Component)]
+struct Controlstext;
+
+/// A change picked on the controls screen, applied once the input is read.
+enum ControlsChange {
+    Rebind(Action, Binding),
+    ToggleAimStick,
+    Reset,
+}
+
+/// Selected row of the controls screen, the rows after the actions toggle
+/// the aim stick and reset everything.
+#[derive(Resource, Default)]
+struct ControlsScreen {
+    selected: usize,
+    capturing: bool,
+    change: Option<ControlsChange>,
+}
+
+const CONTROLS_ROWS: usize = Action::ALL.len() + 2;
+
+/// Game over and controls screens.
+pub struct MenusPlugin;
+
+impl Plugin for MenusPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(setup_gameover
+                            .after(record_highscore)
+                            .in_schedule(OnEnter(GameState::GameOver)))
+            .add_system(gameover_input.in_set(OnUpdate(GameState::GameOver)))
+            .add_system(exit_gameover.in_schedule(OnExit(GameState::GameOver)))
+            .add_system(setup_controls.in_schedule(OnEnter(GameState::Controls)))
+            .add_systems((controls_input, apply_controls_change, controls_text)
+                             .chain()
+                             .in_set(OnUpdate(GameState::Controls)))
+            .add_system(exit_controls.in_schedule(OnExit(GameState::Controls)));
+    }
+}
+
+fn spawn_highscores(
+    commands: &mut Commands,
+    highscores: &HighScores,
+    rules: &Rules,
+    difficulty: Difficulty,
+    asset_server: &AssetServer,
+) {
+    commands.spawn(TextBundle {
+        text: Text::from_section(
+            highscores.table(rules.current().name(), difficulty),
+            TextStyle {
+                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                font_size: 20.0,
+                color: Color::rgb(0.5, 0.5, 1.0),
+            }
+        ),
+        style: Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                bottom: Val::Px(15.0),
+                right: Val::Px(5.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+        .insert(HighScoretext);
+}
+
+fn setup_gameover(
+    mut commands: Commands,
+    game_rng: Res<GameRng>,
+    input_map: Res<InputMap>,
+    highscores: Res<HighScores>,
+    rules: Res<Rules>,
+    difficulty: Res<Difficulty>,
+    asset_server: Res<AssetServer>,
+) {
+    spawn_highscores(&mut commands, &highscores, &rules, *difficulty, &asset_server);
+    commands.spawn(TextBundle {
+        text: Text::from_section(
+            format!(" Game over \n \
+                    \n \
+                    by sabi@nelson-games.de \n \
+                    \n \
+                    seed: {} \n \
+                    \n \
+                    press {} to watch the replay \n \
+                    press {} to play again \n \
+                    press {} for the title", game_rng.seed,
+                    input_map.describe(Action::WatchReplay),
+                    input_map.describe(Action::Restart),
+                    input_map.describe(Action::Confirm)),
+            TextStyle {
+                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                font_size: 48.0,
+                color: Color::rgb(0.5, 0.5, 1.0),
+            }
+        ),
+        style: Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(15.0),
+                left: Val::Px(5.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+        .insert(GameOvertext);
+}
+
+fn gameover_input(
+    mut commands: Commands,
+    mut game_state: ResMut<NextState<GameState>>,
+    actions: Actions,
+    recorder: Option<Res<ReplayRecorder>>,
+) {
+    if actions.just_pressed(Action::WatchReplay) {
+        // the run that just ended, or the last saved one after watching a replay
+        match recorder.map(|recorder| recorder.replay.clone()).or_else(Replay::load_last) {
+            Some(replay) => commands.insert_resource(ReplayPlayer::new(replay)),
+            None => warn!("no replay to watch"),
+        }
+        game_state.set(GameState::GameStart);
+    } else if actions.just_pressed(Action::Restart) {
+        game_state.set(GameState::Playing);
+    } else if actions.just_pressed(Action::Confirm) {
+        game_state.set(GameState::GameStart);
+    }
+}
+
+fn exit_gameover(
+    mut commands: Commands,
+    query: Query<Entity, Or<(With<GameOvertext>, With<HighScoretext>)>>
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
+
+fn setup_controls(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    commands.init_resource::<ControlsScreen>();
+    commands.spawn(TextBundle {
+        text: Text::from_section(
+            "",
+            TextStyle {
+                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                font_size: 18.0,
+                color: Color::rgb(0.5, 0.5, 1.0),
+            }
+        ),
+        style: Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(15.0),
+                left: Val::Px(5.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+        .insert(Controlstext);
+}
+
+fn controls_input(
+    mut game_state: ResMut<NextState<GameState>>,
+    mut screen: ResMut<ControlsScreen>,
+    actions: Actions,
+) {
+    if screen.capturing {
+        if let Some(binding) = actions.any_just_pressed() {
+            screen.change = Some(ControlsChange::Rebind(Action::ALL[screen.selected], binding));
+            screen.capturing = false;
+        }
+        return;
+    }
+    if actions.just_pressed(Action::MenuUp) {
+        screen.selected = (screen.selected + CONTROLS_ROWS - 1) % CONTROLS_ROWS;
+    } else if actions.just_pressed(Action::MenuDown) {
+        screen.selected = (screen.selected + 1) % CONTROLS_ROWS;
+    } else if actions.just_pressed(Action::Confirm) {
+        match screen.selected {
+            row if row < Action::ALL.len() => screen.capturing = true,
+            row if row == Action::ALL.len() => screen.change = Some(ControlsChange::ToggleAimStick),
+            _ => screen.change = Some(ControlsChange::Reset),
+        }
+    } else if actions.just_pressed(Action::Back) {
+        game_state.set(GameState::GameStart);
+    }
+}
+
+fn apply_controls_change(
+    mut screen: ResMut<ControlsScreen>,
+    mut input_map: ResMut<InputMap>,
+) {
+    match screen.change.take() {
+        Some(ControlsChange::Rebind(action, binding)) => input_map.rebind(action, binding),
+        Some(ControlsChange::ToggleAimStick) => {
+            input_map.aim_stick = match input_map.aim_stick {
+                AimStick::Left => AimStick::Right,
+                AimStick::Right => AimStick::Left,
+            };
+        }
+        Some(ControlsChange::Reset) => *input_map = InputMap::default(),
+        None => {}
+    }
+}
+
+fn controls_text(
+    screen: Res<ControlsScreen>,
+    input_map: Res<InputMap>,
+    mut query: Query<&mut Text, With<Controlstext>>
+) {
+    let marker = |row: usize| if row == screen.selected { ">" } else { " " };
+    let mut text = " Controls \n \n".to_string();
+    for (row, action) in Action::ALL.into_iter().enumerate() {
+        let bindings = if screen.capturing && row == screen.selected {
+            "press a key or button".to_string()
+        } else {
+            input_map.bindings(action).iter().map(|b| b.to_string()).collect::<Vec<_>>().join(", ")
+        };
+        text.push_str(&format!(" {} {}: {} \n", marker(row), action, bindings));
+    }
+    text.push_str(&format!(" {} aim stick: {:?} \n", marker(Action::ALL.len()), input_map.aim_stick));
+    text.push_str(&format!(" {} reset to defaults \n \n", marker(Action::ALL.len() + 1)));
+    text.push_str(&format!(" {} / {}: choose   {}: change   {}: done",
+                           input_map.describe(Action::MenuUp), input_map.describe(Action::MenuDown),
+                           input_map.describe(Action::Confirm), input_map.describe(Action::Back)));
+    for mut controls_text in query.iter_mut() {
+        controls_text.sections[0].value = text.clone();
+    }
+}
+
+fn exit_controls(
+    mut commands: Commands,
+    query: Query<Entity, With<Controlstext>>
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<ControlsScreen>();
+}
@@ -0,0 +1,153 @@
+use bevy::prelude::*;
+use bevy::window::WindowFocused;
+use bevy_rapier3d::prelude::*;
+
+use crate::controls::{Action, Actions};
+use crate::game::{end_run, GameState};
+use crate::replay::ReplayPlayer;
+
+#[derive(Component)]
+struct Pausetext;
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum PauseOption {
+    Resume,
+    Restart,
+    Quit,
+}
+
+impl PauseOption {
+    const ALL: [PauseOption; 3] = [PauseOption::Resume, PauseOption::Restart, PauseOption::Quit];
+
+    fn label(&self) -> &'static str {
+        match self {
+            PauseOption::Resume => "resume",
+            PauseOption::Restart => "restart",
+            PauseOption::Quit => "quit to title",
+        }
+    }
+}
+
+#[derive(Resource, Default)]
+struct PauseMenu {
+    selected: usize
+}
+
+/// Stops a run on the pause key or when the window loses focus. While paused the
+/// physics, the clock and the card spawns stand still.
+pub struct PausePlugin;
+
+impl Plugin for PausePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(pause.in_set(OnUpdate(GameState::Playing)))
+            .add_system(setup_pause.in_schedule(OnEnter(GameState::Paused)))
+            .add_system(pause_menu.in_set(OnUpdate(GameState::Paused)))
+            .add_system(exit_pause.in_schedule(OnExit(GameState::Paused)));
+    }
+}
+
+fn pause(
+    mut game_state: ResMut<NextState<GameState>>,
+    actions: Actions,
+    mut focus: EventReader<WindowFocused>,
+) {
+    let focus_lost = focus.iter().any(|event| !event.focused);
+    if focus_lost || actions.just_pressed(Action::Pause) {
+        game_state.set(GameState::Paused);
+    }
+}
+
+fn set_physics_active(rapier_config: Option<ResMut<RapierConfiguration>>, active: bool) {
+    if let Some(mut rapier_config) = rapier_config {
+        rapier_config.physics_pipeline_active = active;
+    }
+}
+
+fn pause_text(menu: &PauseMenu) -> String {
+    let mut text = " Paused \n \n".to_string();
+    for (row, option) in PauseOption::ALL.into_iter().enumerate() {
+        let marker = if row == menu.selected { ">" } else { " " };
+        text.push_str(&format!(" {} {} \n", marker, option.label()));
+    }
+    text
+}
+
+fn setup_pause(
+    mut commands: Commands,
+    rapier_config: Option<ResMut<RapierConfiguration>>,
+    asset_server: Res<AssetServer>,
+) {
+    set_physics_active(rapier_config, false);
+    let menu = PauseMenu::default();
+    commands.spawn(TextBundle {
+        text: Text::from_section(
+            pause_text(&menu),
+            TextStyle {
+                font: asset_server.load("fonts/FiraSans-Bold.ttf"),
+                font_size: 48.0,
+                color: Color::rgb(0.5, 0.5, 1.0),
+            }
+        ),
+        style: Style {
+            position_type: PositionType::Absolute,
+            position: UiRect {
+                top: Val::Px(150.0),
+                left: Val::Px(300.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+        .insert(Pausetext);
+    commands.insert_resource(menu);
+}
+
+fn pause_menu(
+    mut commands: Commands,
+    mut game_state: ResMut<NextState<GameState>>,
+    mut menu: ResMut<PauseMenu>,
+    actions: Actions,
+    mut query: Query<&mut Text, With<Pausetext>>
+) {
+    let options = PauseOption::ALL.len();
+    if actions.just_pressed(Action::MenuUp) {
+        menu.selected = (menu.selected + options - 1) % options;
+    } else if actions.just_pressed(Action::MenuDown) {
+        menu.selected = (menu.selected + 1) % options;
+    } else if actions.just_pressed(Action::Pause) || actions.just_pressed(Action::Back) {
+        game_state.set(GameState::Playing);
+    } else if actions.just_pressed(Action::Confirm) {
+        match PauseOption::ALL[menu.selected] {
+            PauseOption::Resume => game_state.set(GameState::Playing),
+            // leaving a watched replay for good, a restart is a run of its own
+            PauseOption::Restart => {
+                commands.add(end_run);
+                commands.remove_resource::<ReplayPlayer>();
+                game_state.set(GameState::Playing);
+            }
+            PauseOption::Quit => {
+                commands.add(end_run);
+                commands.remove_resource::<ReplayPlayer>();
+                game_state.set(GameState::GameStart);
+            }
+        }
+    }
+    if menu.is_changed() {
+        for mut text in query.iter_mut() {
+            text.sections[0].value = pause_text(&menu);
+        }
+    }
+}
+
+fn exit_pause(
+    mut commands: Commands,
+    rapier_config: Option<ResMut<RapierConfiguration>>,
+    query: Query<Entity, With<Pausetext>>
+) {
+    set_physics_active(rapier_config, true);
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<PauseMenu>();
+}
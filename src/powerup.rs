@@ -0,0 +1,303 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use rand::Rng;
+
+use crate::assets::GameAssets;
+use crate::audio::{SoundEffect, SoundEvent};
+use crate::difficulty::{DifficultySettings, Escalation};
+use crate::effects::{card_effect_color, Burst, CreateEffectEvent};
+use crate::game::{start_run, starting_run, EndRun, FrameTime, GameRng, GameState, TimeScale};
+use crate::rules::{Rules, Stack};
+use crate::ship::Laser;
+use crate::solitaire::{card_slot, card_spawn_point, collision, falling_card, spawn_card,
+                       Card, Decoy, Talon, CARD_LIMIT_Y};
+use crate::tween::CardTweening;
+
+/// What a special card does when it is shot.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum PowerUp {
+    /// Puts the next card the stack needs in its place.
+    Wildcard,
+    /// Slows the falling cards and the lasers down for a while.
+    SlowMotion,
+    /// Takes the next wrong hit without a mistake.
+    Shield,
+    /// Sweeps every falling card that is not needed next off the field.
+    ClearWrong,
+}
+
+impl PowerUp {
+    pub const ALL: [PowerUp; 4] = [PowerUp::Wildcard, PowerUp::SlowMotion, PowerUp::Shield, PowerUp::ClearWrong];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            PowerUp::Wildcard => "wildcard",
+            PowerUp::SlowMotion => "slow motion",
+            PowerUp::Shield => "shield",
+            PowerUp::ClearWrong => "clear",
+        }
+    }
+
+    /// The jokers are the wildcard and the shield, the others show a tinted card back.
+    fn look(&self) -> (&'static str, Color) {
+        match self {
+            PowerUp::Wildcard => ("cards/card_joker_red.png", Color::WHITE),
+            PowerUp::Shield => ("cards/card_joker_black.png", Color::WHITE),
+            PowerUp::SlowMotion => ("cards/card_back.png", Color::rgb(0.5, 0.7, 1.0)),
+            PowerUp::ClearWrong => ("cards/card_back.png", Color::rgb(1.0, 0.7, 0.3)),
+        }
+    }
+
+    fn spark_color(&self) -> Color {
+        match self {
+            PowerUp::Wildcard => Color::GOLD,
+            PowerUp::Shield => Color::SILVER,
+            PowerUp::SlowMotion | PowerUp::ClearWrong => self.look().1,
+        }
+    }
+}
+
+/// A falling special card.
+#[derive(Component)]
+pub struct PowerUpCard(pub PowerUp);
+
+/// A special card was shot.
+pub struct PowerUpEvent(pub PowerUp);
+
+#[derive(Resource)]
+struct PowerUpTimer(Timer);
+
+/// Abilities the player collected and has not used up yet.
+#[derive(Resource, Default)]
+pub struct PowerUps {
+    pub shields: u32,
+    /// Seconds of slow motion left.
+    pub slow_motion: f32,
+}
+
+impl PowerUps {
+    /// Uses up a shield, false if there is none.
+    pub fn use_shield(&mut self) -> bool {
+        if self.shields == 0 {
+            return false;
+        }
+        self.shields -= 1;
+        true
+    }
+}
+
+const SLOW_MOTION_TIME: f32 = 5.0;
+const SLOW_MOTION_SCALE: f32 = 0.4;
+
+/// Special cards falling now and then, and their effects.
+pub struct PowerUpPlugin;
+
+impl Plugin for PowerUpPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PowerUps>()
+            .add_event::<PowerUpEvent>()
+            .add_event::<CreateEffectEvent>()
+            .add_event::<SoundEvent>()
+            .add_system(start_powerups
+                            .after(start_run)
+                            .run_if(starting_run)
+                            .in_schedule(OnEnter(GameState::Playing)))
+            .add_system(spawn_powerup
+                            .after(spawn_card)
+                            .in_set(OnUpdate(GameState::Playing))
+                            .run_if(resource_exists::<PowerUpTimer>()))
+            .add_systems((collect_powerups, apply_powerups, play_wildcard, clear_wrong_cards)
+                             .chain()
+                             .after(collision)
+                             .in_set(OnUpdate(GameState::Playing)))
+            .add_systems((slow_motion, despawn_powerups).in_set(OnUpdate(GameState::Playing)))
+            .add_system(clear_powerups.in_schedule(EndRun));
+    }
+}
+
+fn start_powerups(
+    mut commands: Commands,
+    settings: Res<DifficultySettings>,
+) {
+    if let Some(interval) = settings.powerup_interval {
+        commands.insert_resource(PowerUpTimer(Timer::from_seconds(interval, TimerMode::Repeating)));
+    }
+    commands.insert_resource(PowerUps::default());
+}
+
+fn spawn_powerup(
+    mut commands: Commands,
+    mut timer: ResMut<PowerUpTimer>,
+    mut game_rng: ResMut<GameRng>,
+    frame_time: Res<FrameTime>,
+    settings: Res<DifficultySettings>,
+    escalation: Res<Escalation>,
+    asset_server: Res<AssetServer>,
+    mut assets: ResMut<GameAssets>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if !timer.0.tick(frame_time.world_delta).just_finished() {
+        return;
+    }
+    let rng = &mut game_rng.rng;
+    let powerup = PowerUp::ALL[rng.gen_range(0..PowerUp::ALL.len())];
+    let x = rng.gen_range(-settings.card_limit_x..settings.card_limit_x);
+    let (texture, tint) = powerup.look();
+    let (mesh, material) = assets.card_look(texture, tint, &asset_server, &mut materials);
+    commands.spawn(PbrBundle {
+        mesh,
+        material,
+        transform: Transform::from_translation(card_spawn_point(x)),
+        ..Default::default()
+    })
+        .insert(falling_card(escalation.gravity_scale))
+        .insert(PowerUpCard(powerup));
+}
+
+fn collect_powerups(
+    mut commands: Commands,
+    mut collision_events: EventReader<CollisionEvent>,
+    query_powerup: Query<(Entity, &Transform, &PowerUpCard)>,
+    query_laser: Query<Entity, With<Laser>>,
+    mut event_create_effect: EventWriter<CreateEffectEvent>,
+    mut event_powerup: EventWriter<PowerUpEvent>,
+    mut event_sound: EventWriter<SoundEvent>,
+) {
+    for event in collision_events.iter() {
+        let CollisionEvent::Started(e1, e2, _) = event else {
+            continue;
+        };
+        let hit = |entity: Entity| entity == *e1 || entity == *e2;
+        let Some((entity, transform, powerup)) = query_powerup.iter().find(|(entity, _, _)| hit(*entity)) else {
+            continue;
+        };
+        let Some(laser) = query_laser.iter().find(|laser| hit(*laser)) else {
+            continue;
+        };
+        commands.entity(laser).despawn_recursive();
+        commands.entity(entity).despawn_recursive();
+        event_create_effect.send(CreateEffectEvent {
+            position: transform.translation,
+            color: powerup.0.spark_color(),
+            burst: Burst::Sparks,
+        });
+        event_powerup.send(PowerUpEvent(powerup.0));
+        event_sound.send(SoundEvent(SoundEffect::Hit));
+    }
+}
+
+fn apply_powerups(
+    mut events: EventReader<PowerUpEvent>,
+    mut powerups: ResMut<PowerUps>,
+) {
+    for PowerUpEvent(powerup) in events.iter() {
+        match powerup {
+            PowerUp::Shield => powerups.shields += 1,
+            PowerUp::SlowMotion => powerups.slow_motion = SLOW_MOTION_TIME,
+            PowerUp::Wildcard | PowerUp::ClearWrong => {}
+        }
+    }
+}
+
+/// Places the next needed card, taken from the talon or caught while falling.
+fn play_wildcard(
+    mut commands: Commands,
+    mut events: EventReader<PowerUpEvent>,
+    mut state: ResMut<NextState<GameState>>,
+    rules: Res<Rules>,
+    tweening: Res<CardTweening>,
+    mut stack: ResMut<Stack>,
+    mut talon: ResMut<Talon>,
+    query_card: Query<(Entity, &Transform, &Card), (With<RigidBody>, Without<Decoy>)>,
+    asset_server: Res<AssetServer>,
+    mut assets: ResMut<GameAssets>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    for _ in events.iter().filter(|event| event.0 == PowerUp::Wildcard) {
+        if let Some(index) = talon.cards.iter().position(|face| stack.accepts(rules.current(), face)) {
+            let face = talon.cards.remove(index);
+            let (pile, slot) = stack.place(rules.current(), face);
+            let (mesh, material) = assets.card_look(&face.texture_path(), Color::WHITE,
+                                                    &asset_server, &mut materials);
+            commands.spawn(PbrBundle {
+                mesh,
+                material,
+                transform: Transform::from_translation(card_spawn_point(0.0)),
+                ..Default::default()
+            })
+                .insert(Card { face })
+                .insert(tweening.place(Transform::from_translation(card_spawn_point(0.0)),
+                                       card_slot(stack.piles.len(), pile, slot)));
+        } else if let Some((entity, transform, card)) = query_card.iter()
+            .filter(|(_, _, card)| stack.accepts(rules.current(), &card.face))
+            .min_by(|(_, a, _), (_, b, _)| a.translation.y.total_cmp(&b.translation.y)) {
+            let (pile, slot) = stack.place(rules.current(), card.face);
+            commands.entity(entity)
+                .remove::<RigidBody>()
+                .remove::<Collider>()
+                .insert(tweening.place(*transform, card_slot(stack.piles.len(), pile, slot)));
+        }
+        if stack.is_complete() {
+            state.set(GameState::GameOver);
+        }
+    }
+}
+
+fn clear_wrong_cards(
+    mut commands: Commands,
+    mut events: EventReader<PowerUpEvent>,
+    rules: Res<Rules>,
+    stack: Res<Stack>,
+    mut talon: ResMut<Talon>,
+    query_card: Query<(Entity, &Transform, &Card, Option<&Decoy>), With<RigidBody>>,
+    mut event_create_effect: EventWriter<CreateEffectEvent>,
+) {
+    for _ in events.iter().filter(|event| event.0 == PowerUp::ClearWrong) {
+        for (entity, transform, card, decoy) in query_card.iter() {
+            if decoy.is_none() && stack.accepts(rules.current(), &card.face) {
+                continue;
+            }
+            if decoy.is_none() {
+                talon.cards.push(card.face);
+            }
+            commands.entity(entity).despawn_recursive();
+            event_create_effect.send(CreateEffectEvent {
+                position: transform.translation,
+                color: card_effect_color(&card.face),
+                burst: Burst::Sparks,
+            });
+        }
+    }
+}
+
+/// Runs the slow motion down on the clock, not on the slowed world time.
+fn slow_motion(
+    frame_time: Res<FrameTime>,
+    mut powerups: ResMut<PowerUps>,
+    mut time_scale: ResMut<TimeScale>,
+) {
+    powerups.slow_motion = (powerups.slow_motion - frame_time.delta.as_secs_f32()).max(0.0);
+    time_scale.0 = if powerups.slow_motion > 0.0 { SLOW_MOTION_SCALE } else { 1.0 };
+}
+
+fn despawn_powerups(
+    mut commands: Commands,
+    query: Query<(Entity, &Transform), With<PowerUpCard>>,
+) {
+    for (entity, transform) in query.iter() {
+        if transform.translation.y <= CARD_LIMIT_Y {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn clear_powerups(
+    mut commands: Commands,
+    query: Query<Entity, With<PowerUpCard>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<PowerUpTimer>();
+    commands.insert_resource(PowerUps::default());
+}
@@ -0,0 +1,171 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::difficulty::{Difficulty, DifficultySettings};
+use crate::storage;
+
+const LAST_REPLAY_KEY: &str = "replay_last";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ReplayEvent {
+    /// A card entered the play field at `x`.
+    Spawn { card: String, x: f32 },
+    /// A weapon was fired at the point the player aimed at.
+    Fire {
+        target: [f32; 3],
+        /// Replays from before the weapon choice fired the laser.
+        #[serde(default = "default_weapon")]
+        weapon: String,
+    },
+    /// The pilot changed the steering of the ship.
+    Steer { steering: [f32; 2] },
+}
+
+fn default_weapon() -> String {
+    "laser".to_string()
+}
+
+/// Everything needed to re-simulate a run: the seed, the rules, the difficulty,
+/// the length of every frame and the inputs of the frames they happened in.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Replay {
+    pub seed: u64,
+    pub rules: String,
+    #[serde(default)]
+    pub difficulty: Difficulty,
+    /// Kept along with the difficulty, the custom values may have changed since.
+    #[serde(default)]
+    pub settings: DifficultySettings,
+    pub frames: Vec<f32>,
+    pub events: Vec<(u32, ReplayEvent)>,
+}
+
+impl Replay {
+    pub fn new(seed: u64, rules: &str, difficulty: Difficulty, settings: DifficultySettings) -> Self {
+        Self {
+            seed,
+            rules: rules.to_string(),
+            difficulty,
+            settings,
+            ..Default::default()
+        }
+    }
+
+    pub fn to_json(&self) -> Result<String, String> {
+        serde_json::to_string(self).map_err(|e| e.to_string())
+    }
+
+    pub fn from_json(text: &str) -> Result<Self, String> {
+        serde_json::from_str(text).map_err(|e| e.to_string())
+    }
+
+    pub fn save_last(&self) {
+        if let Err(err) = self.to_json().and_then(|text| storage::save(LAST_REPLAY_KEY, &text)) {
+            error!("could not save replay: {}", err);
+        }
+    }
+
+    pub fn load_last() -> Option<Self> {
+        Self::from_json(&storage::load(LAST_REPLAY_KEY)?).ok()
+    }
+
+    /// Reads a replay file shared outside the game's own storage.
+    pub fn load_file(path: &str) -> Result<Self, String> {
+        std::fs::read_to_string(path)
+            .map_err(|e| e.to_string())
+            .and_then(|text| Self::from_json(&text))
+    }
+}
+
+/// Collects the replay of the run being played.
+#[derive(Resource)]
+pub struct ReplayRecorder {
+    pub replay: Replay
+}
+
+impl ReplayRecorder {
+    pub fn record(&mut self, event: ReplayEvent) {
+        let frame = self.replay.frames.len().saturating_sub(1) as u32;
+        self.replay.events.push((frame, event));
+    }
+}
+
+/// Plays a recorded run back instead of taking player input.
+#[derive(Resource)]
+pub struct ReplayPlayer {
+    pub replay: Replay,
+    /// Number of frames played so far.
+    pub frame: usize,
+    /// Spawns that did not match the recording.
+    pub desyncs: usize,
+    next_fire: usize,
+    next_spawn: usize,
+    next_steer: usize,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            frame: 0,
+            desyncs: 0,
+            next_fire: 0,
+            next_spawn: 0,
+            next_steer: 0
+        }
+    }
+
+    /// Starts the next frame and returns its length, `None` once the recording is over.
+    pub fn next_frame(&mut self) -> Option<f32> {
+        let delta = self.replay.frames.get(self.frame).copied()?;
+        self.frame += 1;
+        Some(delta)
+    }
+
+    /// Fire targets and weapons of the current frame.
+    pub fn take_fires(&mut self) -> Vec<(Vec3, String)> {
+        let mut targets = vec![];
+        while let Some((frame, event)) = self.replay.events.get(self.next_fire) {
+            if *frame as usize >= self.frame {
+                break;
+            }
+            if let ReplayEvent::Fire { target, weapon } = event {
+                targets.push((Vec3::from_array(*target), weapon.clone()));
+            }
+            self.next_fire += 1;
+        }
+        targets
+    }
+
+    /// Last steering change of the current frame, if there was one.
+    pub fn take_steering(&mut self) -> Option<Vec2> {
+        let mut steering = None;
+        while let Some((frame, event)) = self.replay.events.get(self.next_steer) {
+            if *frame as usize >= self.frame {
+                break;
+            }
+            if let ReplayEvent::Steer { steering: recorded } = event {
+                steering = Some(Vec2::from_array(*recorded));
+            }
+            self.next_steer += 1;
+        }
+        steering
+    }
+
+    /// Compares a spawn of the re-simulated run with the recording,
+    /// false means the playback went out of sync.
+    pub fn check_spawn(&mut self, card: &str, x: f32) -> bool {
+        let mut in_sync = false;
+        while let Some((_, event)) = self.replay.events.get(self.next_spawn) {
+            self.next_spawn += 1;
+            if let ReplayEvent::Spawn { card: recorded, x: recorded_x } = event {
+                in_sync = recorded == card && *recorded_x == x;
+                break;
+            }
+        }
+        if !in_sync {
+            self.desyncs += 1;
+        }
+        in_sync
+    }
+}
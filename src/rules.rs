@@ -0,0 +1,256 @@
+use bevy::prelude::*;
+
+use crate::card::{PlayingCard, Rank, Suit};
+
+/// A solitaire variant: which card has to be shot next.
+pub trait SolitaireRules: Send + Sync + 'static {
+    fn name(&self) -> &'static str;
+
+    /// Order in which the ranks have to be shot.
+    fn rank_order(&self) -> Vec<Rank> {
+        Rank::ALL.to_vec()
+    }
+
+    /// Number of piles built at the same time, each following the rank order.
+    fn piles(&self) -> usize {
+        1
+    }
+
+    /// Pile a card belongs on.
+    fn pile(&self, _card: &PlayingCard) -> usize {
+        0
+    }
+
+    fn pile_name(&self, _pile: usize) -> String {
+        "Next card".to_string()
+    }
+
+    /// Extra condition on top of the rank, given the cards already on the pile.
+    fn matches(&self, _placed: &[PlayingCard], _card: &PlayingCard) -> bool {
+        true
+    }
+
+    /// Next card of a pile as shown on the scoreboard.
+    fn hint(&self, _placed: &[PlayingCard], next: Rank) -> String {
+        next.to_string()
+    }
+}
+
+pub struct Ascending;
+
+impl SolitaireRules for Ascending {
+    fn name(&self) -> &'static str {
+        "2 to A"
+    }
+}
+
+pub struct Descending;
+
+impl SolitaireRules for Descending {
+    fn name(&self) -> &'static str {
+        "A to 2"
+    }
+
+    fn rank_order(&self) -> Vec<Rank> {
+        Rank::ALL.into_iter().rev().collect()
+    }
+}
+
+/// Ascending, every card in the suit of the first one.
+pub struct SameSuit;
+
+impl SolitaireRules for SameSuit {
+    fn name(&self) -> &'static str {
+        "same suit"
+    }
+
+    fn matches(&self, placed: &[PlayingCard], card: &PlayingCard) -> bool {
+        match placed.first() {
+            Some(first) => first.suit() == card.suit(),
+            None => true,
+        }
+    }
+
+    fn hint(&self, placed: &[PlayingCard], next: Rank) -> String {
+        match placed.first().and_then(|c| c.suit()) {
+            Some(suit) => format!("{} {}", next, suit),
+            None => next.to_string(),
+        }
+    }
+}
+
+/// Klondike style: descending with alternating colors.
+pub struct AlternatingColors;
+
+impl SolitaireRules for AlternatingColors {
+    fn name(&self) -> &'static str {
+        "alternating colors"
+    }
+
+    fn rank_order(&self) -> Vec<Rank> {
+        Rank::ALL.into_iter().rev().collect()
+    }
+
+    fn matches(&self, placed: &[PlayingCard], card: &PlayingCard) -> bool {
+        match placed.last() {
+            Some(last) => last.color() != card.color(),
+            None => true,
+        }
+    }
+
+    fn hint(&self, placed: &[PlayingCard], next: Rank) -> String {
+        match placed.last() {
+            Some(last) => format!("{} not {}", next, last.color()),
+            None => next.to_string(),
+        }
+    }
+}
+
+/// One ascending pile per suit, the run ends when the whole deck is placed.
+pub struct FourFoundations;
+
+impl SolitaireRules for FourFoundations {
+    fn name(&self) -> &'static str {
+        "four foundations"
+    }
+
+    fn piles(&self) -> usize {
+        Suit::ALL.len()
+    }
+
+    fn pile(&self, card: &PlayingCard) -> usize {
+        card.suit()
+            .and_then(|suit| Suit::ALL.iter().position(|s| *s == suit))
+            .unwrap_or(0)
+    }
+
+    fn pile_name(&self, pile: usize) -> String {
+        Suit::ALL[pile].to_string()
+    }
+
+    fn matches(&self, _placed: &[PlayingCard], card: &PlayingCard) -> bool {
+        !card.is_joker()
+    }
+}
+
+/// The available rule sets and the one chosen on the start screen.
+#[derive(Resource)]
+pub struct Rules {
+    variants: Vec<Box<dyn SolitaireRules>>,
+    selected: usize
+}
+
+impl Rules {
+    pub fn add(&mut self, rules: impl SolitaireRules) {
+        self.variants.push(Box::new(rules));
+    }
+
+    pub fn current(&self) -> &dyn SolitaireRules {
+        self.variants[self.selected].as_ref()
+    }
+
+    /// Selects the rules with the given name, false if there are none.
+    pub fn select_by_name(&mut self, name: &str) -> bool {
+        match self.variants.iter().position(|rules| rules.name() == name) {
+            Some(index) => {
+                self.selected = index;
+                true
+            }
+            None => false,
+        }
+    }
+
+    pub fn select_next(&mut self) {
+        self.selected = (self.selected + 1) % self.variants.len();
+    }
+
+    pub fn select_previous(&mut self) {
+        self.selected = (self.selected + self.variants.len() - 1) % self.variants.len();
+    }
+}
+
+impl Default for Rules {
+    fn default() -> Self {
+        Self {
+            variants: vec![Box::new(Ascending), Box::new(Descending),
+                           Box::new(SameSuit), Box::new(AlternatingColors),
+                           Box::new(FourFoundations)],
+            selected: 0
+        }
+    }
+}
+
+/// Cards shot in the right order during a run.
+#[derive(Resource)]
+pub struct Stack {
+    pub cards: Vec<Rank>,
+    pub piles: Vec<Vec<PlayingCard>>,
+    /// All placed cards, oldest first.
+    pub texts: Vec<PlayingCard>,
+    pub current: usize
+}
+
+impl Stack {
+    pub fn new(rules: &dyn SolitaireRules) -> Self {
+        Self {
+            cards: rules.rank_order(),
+            piles: vec![vec![]; rules.piles()],
+            texts: vec![],
+            current: 0
+        }
+    }
+
+    pub fn next_rank(&self, pile: usize) -> Option<Rank> {
+        self.cards.get(self.piles[pile].len()).copied()
+    }
+
+    pub fn accepts(&self, rules: &dyn SolitaireRules, card: &PlayingCard) -> bool {
+        let pile = rules.pile(card);
+        self.next_rank(pile).is_some()
+            && card.rank() == self.next_rank(pile)
+            && rules.matches(&self.piles[pile], card)
+    }
+
+    /// Puts a card on its pile and returns the pile and the position in it.
+    pub fn place(&mut self, rules: &dyn SolitaireRules, card: PlayingCard) -> (usize, usize) {
+        let pile = rules.pile(&card);
+        self.piles[pile].push(card);
+        self.texts.push(card);
+        self.current += 1;
+        (pile, self.piles[pile].len() - 1)
+    }
+
+    /// Removes the last placed card.
+    pub fn take_back(&mut self) -> Option<PlayingCard> {
+        let card = self.texts.pop()?;
+        self.current -= 1;
+        if let Some(pile) = self.piles.iter_mut().find(|pile| pile.last() == Some(&card)) {
+            pile.pop();
+        }
+        Some(card)
+    }
+
+    pub fn is_complete(&self) -> bool {
+        self.current == self.cards.len() * self.piles.len()
+    }
+
+    /// Share of the cards placed so far, from 0 to 1.
+    pub fn progress(&self) -> f32 {
+        self.current as f32 / (self.cards.len() * self.piles.len()).max(1) as f32
+    }
+
+    pub fn hint(&self, rules: &dyn SolitaireRules, pile: usize) -> String {
+        match self.next_rank(pile) {
+            Some(next) => rules.hint(&self.piles[pile], next),
+            None => "-".to_string(),
+        }
+    }
+}
+
+pub struct RulesPlugin;
+
+impl Plugin for RulesPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Rules>();
+    }
+}
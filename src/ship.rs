@@ -0,0 +1,341 @@
+use std::fmt;
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use serde::{Deserialize, Serialize};
+
+use crate::assets::GameAssets;
+use crate::audio::{SoundEffect, SoundEvent};
+use crate::difficulty::DifficultySettings;
+use crate::game::{EndRun, FrameTime, GameState};
+use crate::replay::ReplayPlayer;
+use crate::storage;
+use crate::weapon::{Arsenal, Homing, Piercing, ShotKind, SingleLaser, Weapon, WeaponKind};
+
+const FLIGHT_MODE_KEY: &str = "flight_mode";
+
+pub const SHIP_POSTION: Vec3 = Vec3::new(0.0, -1.0, -8.0);
+
+/// How far the ship may fly from [`SHIP_POSTION`], sideways and up or down.
+pub const SHIP_BOUNDS: Vec2 = Vec2::new(4.0, 1.5);
+
+/// The ship, its transform turns towards `target` while the model below it banks.
+#[derive(Component)]
+pub struct Ship {
+    pub velocity: Vec2,
+    /// Point the ship turns to, lasers fly straight to their own target.
+    pub target: Vec3,
+}
+
+impl Default for Ship {
+    fn default() -> Self {
+        Self {
+            velocity: Vec2::ZERO,
+            target: SHIP_POSTION + Vec3::NEG_Z,
+        }
+    }
+}
+
+#[derive(Component)]
+struct ShipModel;
+
+#[derive(Component)]
+pub struct Laser;
+
+/// Turns the ship towards `target` and fires the named weapon, if lasers are left.
+pub struct CreateLaserEvent {
+    pub target:Vec3,
+    pub weapon:String
+}
+
+/// Whether the ship stays in place or can be flown across the play field.
+#[derive(Resource, Debug, Clone, Copy, Default, Eq, PartialEq, Serialize, Deserialize)]
+pub enum FlightMode {
+    #[default]
+    Fixed,
+    /// Sideways only.
+    Strafe,
+    /// Sideways, up and down.
+    Free,
+}
+
+impl FlightMode {
+    pub const ALL: [FlightMode; 3] = [FlightMode::Fixed, FlightMode::Strafe, FlightMode::Free];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            FlightMode::Fixed => "fixed",
+            FlightMode::Strafe => "strafe",
+            FlightMode::Free => "free",
+        }
+    }
+
+    pub fn next(&self) -> Self {
+        let index = Self::ALL.iter().position(|m| m == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
+
+    pub fn previous(&self) -> Self {
+        let index = Self::ALL.iter().position(|m| m == self).unwrap_or(0);
+        Self::ALL[(index + Self::ALL.len() - 1) % Self::ALL.len()]
+    }
+
+    /// The part of a steering input the mode lets through.
+    pub fn restrict(&self, steering: Vec2) -> Vec2 {
+        match self {
+            FlightMode::Fixed => Vec2::ZERO,
+            FlightMode::Strafe => Vec2::new(steering.x, 0.0),
+            FlightMode::Free => steering,
+        }
+    }
+
+    pub fn load() -> Self {
+        storage::load(FLIGHT_MODE_KEY)
+            .and_then(|text| serde_json::from_str(&text).ok())
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self) {
+        let result = serde_json::to_string(self)
+            .map_err(|e| e.to_string())
+            .and_then(|text| storage::save(FLIGHT_MODE_KEY, &text));
+        if let Err(err) = result {
+            error!("could not save flight mode: {}", err);
+        }
+    }
+}
+
+impl fmt::Display for FlightMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+/// Direction the pilot pushes the ship in, each axis from -1 to 1.
+#[derive(Resource, Default)]
+pub struct Steering(pub Vec2);
+
+/// How fast the ship turns towards its target, in radians per second.
+#[derive(Resource)]
+pub struct TurnRate(pub f32);
+
+/// The ship and its lasers, fired at the targets of [`CreateLaserEvent`]s.
+pub struct ShipPlugin {
+    /// Radians per second the ship turns towards where it aims.
+    pub turn_rate: f32
+}
+
+impl Default for ShipPlugin {
+    fn default() -> Self {
+        Self {
+            turn_rate: 8.0
+        }
+    }
+}
+
+impl Plugin for ShipPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_event::<CreateLaserEvent>()
+            .add_event::<SoundEvent>()
+            .insert_resource(TurnRate(self.turn_rate))
+            .init_resource::<FlightMode>()
+            .init_resource::<Steering>()
+            .init_resource::<GameAssets>()
+            .add_startup_system(setup_ship)
+            .add_systems((spawn_laser, moving).in_set(OnUpdate(GameState::Playing)))
+            .add_systems((fly, turn_ship).after(spawn_laser).in_set(OnUpdate(GameState::Playing)))
+            .add_system(replay_input
+                            .before(spawn_laser)
+                            .in_set(OnUpdate(GameState::Playing))
+                            .run_if(resource_exists::<ReplayPlayer>()))
+            .add_system(reset_ship.in_schedule(EndRun));
+    }
+}
+
+fn setup_ship(
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    commands.spawn(SpatialBundle::from_transform(Transform::from_translation(SHIP_POSTION)))
+        .insert(Ship::default())
+        .with_children(|parent| {
+            parent.spawn(SceneBundle {
+                scene: asset_server.load("models/ship1.glb#Scene0"),
+                ..Default::default()
+            })
+                .insert(ShipModel);
+        });
+}
+
+/// Radians per second a missile turns towards its card.
+const MISSILE_TURN_RATE: f32 = 3.0;
+
+pub fn spawn_laser(
+    mut event_create_laser: EventReader<CreateLaserEvent>,
+    mut commands: Commands,
+    assets: Res<GameAssets>,
+    mut query: Query<(&Transform, &mut Ship)>,
+    query_laser: Query<(), With<Laser>>,
+    settings: Res<DifficultySettings>,
+    mut weapon: Option<ResMut<Weapon>>,
+    mut arsenal: Option<ResMut<Arsenal>>,
+    mut event_sound: EventWriter<SoundEvent>,
+)
+{
+    let mut lasers = query_laser.iter().count();
+    for event in event_create_laser.iter() {
+        let (ship_transform, mut ship) = query.single_mut();
+        ship.target = event.target;
+        // shots leave straight for the target, even while the ship is still turning
+        let aim = (event.target - ship_transform.translation).normalize_or_zero();
+        // a replay shows the weapon it fires; without the weapon plugin it is a plain laser
+        let kind: &dyn WeaponKind = match arsenal.as_mut() {
+            Some(arsenal) => {
+                arsenal.select_by_name(&event.weapon);
+                arsenal.by_name(&event.weapon)
+            }
+            None => &SingleLaser,
+        };
+        let shots = kind.shots(aim);
+        if lasers + shots.len() > settings.max_lasers {
+            continue;
+        }
+        lasers += shots.len();
+        if let Some(weapon) = weapon.as_mut() {
+            weapon.shoot(kind);
+        }
+        event_sound.send(SoundEvent(SoundEffect::Laser));
+        for shot in shots {
+            let size = shot.kind.size();
+            let (mesh, material) = assets.shot(shot.kind);
+            let mut laser = commands.spawn(PbrBundle {
+                mesh,
+                material,
+                transform: Transform::from_translation(ship_transform.translation)
+                    .looking_to(shot.direction, Vec3::Y),
+                ..Default::default()
+            });
+            laser.insert(RigidBody::KinematicVelocityBased)
+                .insert(Sleeping::disabled())
+                .insert(Collider::cuboid(size.x/2.0,
+                                         size.y/2.0,
+                                         size.z/2.0))
+                .insert(Velocity {
+                    linvel: shot.direction*shot.speed,
+                    ..Default::default()
+                })
+                .insert(Laser);
+            match shot.kind {
+                ShotKind::Laser => {}
+                ShotKind::Beam => {
+                    laser.insert(Piercing);
+                }
+                ShotKind::Missile => {
+                    laser.insert(Homing { turn_rate: MISSILE_TURN_RATE });
+                }
+            }
+        }
+    }
+}
+
+/// Ship speed in units per second, reached after `1 / SHIP_ACCELERATION` seconds.
+const SHIP_SPEED: f32 = 6.0;
+const SHIP_ACCELERATION: f32 = 4.0;
+/// Roll of the model at full sideways speed.
+const MAX_BANK: f32 = 0.6;
+
+/// Accelerates the ship towards the steering, keeps it inside [`SHIP_BOUNDS`]
+/// and banks the model into the turn.
+fn fly(
+    steering: Res<Steering>,
+    frame_time: Res<FrameTime>,
+    mut query: Query<(&mut Transform, &mut Ship)>,
+    mut query_model: Query<&mut Transform, (With<ShipModel>, Without<Ship>)>,
+) {
+    let dt = frame_time.delta.as_secs_f32();
+    for (mut transform, mut ship) in query.iter_mut() {
+        let wanted = steering.0.clamp_length_max(1.0) * SHIP_SPEED;
+        let change = (wanted - ship.velocity).clamp_length_max(SHIP_SPEED * SHIP_ACCELERATION * dt);
+        ship.velocity += change;
+
+        let offset = transform.translation.truncate() - SHIP_POSTION.truncate() + ship.velocity * dt;
+        let bounded = offset.clamp(-SHIP_BOUNDS, SHIP_BOUNDS);
+        // stop at the edges instead of pushing against them
+        if bounded.x != offset.x {
+            ship.velocity.x = 0.0;
+        }
+        if bounded.y != offset.y {
+            ship.velocity.y = 0.0;
+        }
+        transform.translation = (SHIP_POSTION.truncate() + bounded).extend(SHIP_POSTION.z);
+
+        for mut model in query_model.iter_mut() {
+            model.rotation = Quat::from_rotation_z(-ship.velocity.x / SHIP_SPEED * MAX_BANK);
+        }
+    }
+}
+
+/// Turns the ship towards its target, at most by the [`TurnRate`].
+fn turn_ship(
+    turn_rate: Res<TurnRate>,
+    frame_time: Res<FrameTime>,
+    mut query: Query<(&mut Transform, &Ship)>,
+) {
+    let max_angle = turn_rate.0 * frame_time.delta.as_secs_f32();
+    for (mut transform, ship) in query.iter_mut() {
+        let wanted = transform.looking_at(ship.target, Vec3::Y).rotation;
+        let angle = transform.rotation.angle_between(wanted);
+        transform.rotation = if angle <= max_angle {
+            wanted
+        } else {
+            transform.rotation.slerp(wanted, max_angle / angle)
+        };
+    }
+}
+
+const MAX_DISTANCE:f32 = 50.0;
+
+fn moving(
+    mut commands: Commands,
+    query: Query<(Entity, &Transform), With<Laser>>,
+    query_ship: Query<&Transform, (With<Ship>, Without<Laser>)>
+){
+    let ship_transform = query_ship.single();
+    for (entity, transform) in query.iter() {
+        if ship_transform.translation.distance(transform.translation) > MAX_DISTANCE {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+fn replay_input(
+    mut player: ResMut<ReplayPlayer>,
+    mut steering: ResMut<Steering>,
+    mut event_create_laser: EventWriter<CreateLaserEvent>,
+) {
+    if let Some(recorded) = player.take_steering() {
+        steering.0 = recorded;
+    }
+    for (target, weapon) in player.take_fires() {
+        event_create_laser.send(CreateLaserEvent { target, weapon });
+    }
+}
+
+fn reset_ship(
+    mut commands: Commands,
+    mut steering: ResMut<Steering>,
+    mut query_ship: Query<(&mut Transform, &mut Ship)>,
+    mut query_model: Query<&mut Transform, (With<ShipModel>, Without<Ship>)>,
+    query_laser: Query<Entity, With<Laser>>,
+) {
+    steering.0 = Vec2::ZERO;
+    for (mut transform, mut ship) in query_ship.iter_mut() {
+        *transform = Transform::from_translation(SHIP_POSTION);
+        *ship = Ship::default();
+    }
+    for mut model in query_model.iter_mut() {
+        *model = Transform::IDENTITY;
+    }
+    for entity in query_laser.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
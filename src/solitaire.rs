@@ -0,0 +1,321 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+use rand::Rng;
+
+use crate::assets::GameAssets;
+use crate::audio::{SoundEffect, SoundEvent};
+use crate::card::PlayingCard;
+use crate::deck::{Deck, DeckHandle, DeckPlugin, DEFAULT_DECK};
+use crate::difficulty::{DifficultySettings, Escalation};
+use crate::effects::{HitEvent, HitOutcome};
+use crate::game::{start_run, starting_run, EndRun, FrameTime, GameRng, GameState, Score};
+use crate::powerup::PowerUps;
+use crate::replay::{ReplayEvent, ReplayPlayer, ReplayRecorder};
+use crate::rules::{Rules, Stack};
+use crate::ship::{Laser, SHIP_POSTION};
+use crate::tween::{CardTweening, Tween, TweenPlugin};
+use crate::weapon::Piercing;
+
+#[derive(Resource)]
+pub struct SpawnTimer(pub Timer);
+
+#[derive(Component)]
+pub struct Card{
+    pub face:PlayingCard
+}
+
+/// A copy of a card already on the stack, thrown in by the difficulty ramp.
+/// Shooting it is a mistake, and it never goes back to the talon.
+#[derive(Component)]
+pub struct Decoy;
+
+/// Cards not yet on the stack, the next falling card is drawn from here.
+#[derive(Resource)]
+pub struct Talon {
+    pub cards: Vec<PlayingCard>
+}
+
+impl Talon {
+    pub fn from_deck(deck: &Deck) -> Self {
+        Self {
+            cards:deck.cards.clone()
+        }
+    }
+}
+
+/// Falling cards, hits on them and the stack they are laid out on.
+pub struct SolitairePlugin {
+    /// Deck definition loaded at startup, unless a [`DeckHandle`] is already present.
+    pub deck_path: String,
+    /// How hit cards fly onto the stack and mistakes take them off again.
+    pub card_tweening: CardTweening,
+}
+
+impl Default for SolitairePlugin {
+    fn default() -> Self {
+        Self {
+            deck_path: DEFAULT_DECK.to_string(),
+            card_tweening: CardTweening::default(),
+        }
+    }
+}
+
+impl Plugin for SolitairePlugin {
+    fn build(&self, app: &mut App) {
+        let deck_path = self.deck_path.clone();
+        app.add_plugin(DeckPlugin)
+            .add_plugin(TweenPlugin)
+            .insert_resource(self.card_tweening.clone())
+            .init_resource::<GameAssets>()
+            .add_event::<HitEvent>()
+            .add_event::<CollisionEvent>()
+            .add_event::<SoundEvent>()
+            .add_startup_system(move |mut commands: Commands,
+                                      asset_server: Res<AssetServer>,
+                                      deck: Option<Res<DeckHandle>>| {
+                if deck.is_none() {
+                    commands.insert_resource(DeckHandle(asset_server.load(deck_path.as_str())));
+                }
+            })
+            .add_system(deal.after(start_run).run_if(starting_run).in_schedule(OnEnter(GameState::Playing)))
+            .add_systems((collision,
+                          despawn_card,
+                          escalate.before(spawn_card),
+                          spawn_card).in_set(OnUpdate(GameState::Playing)))
+            .add_system(clear_cards.in_schedule(EndRun));
+    }
+}
+
+const FIRST_CARD_POSITION: Vec3 = Vec3::new(-15.0, -12.5,-28.0);
+const CARD_STEP:f32 = 1.0;
+const FOUNDATION_CARD_STEP:f32 = 0.5;
+const FOUNDATION_WIDTH:f32 = 9.0;
+
+/// Place of a card on the whiteboard, piles are laid out side by side.
+pub fn card_slot(piles:usize, pile:usize, index:usize) -> Vec3 {
+    let step = if piles > 1 { FOUNDATION_CARD_STEP } else { CARD_STEP };
+    FIRST_CARD_POSITION + Vec3::new(pile as f32 * FOUNDATION_WIDTH + index as f32 * step,
+                                    0.0,
+                                    index as f32 * 0.01)
+}
+
+fn deal(
+    mut commands: Commands,
+    rules: Res<Rules>,
+    settings: Res<DifficultySettings>,
+    decks: Res<Assets<Deck>>,
+    deck: Res<DeckHandle>,
+    mut assets: ResMut<GameAssets>,
+    asset_server: Res<AssetServer>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    commands.insert_resource(SpawnTimer(Timer::from_seconds(settings.spawn_interval, TimerMode::Repeating)));
+    commands.insert_resource(settings.escalation(0.0));
+    let deck = decks.get(&deck.0).expect("game starts only once the deck is loaded");
+    // a material per face up front, spawning a card only picks its handle
+    for face in deck.cards.iter() {
+        assets.card_look(&face.texture_path(), Color::WHITE, &asset_server, &mut materials);
+    }
+    commands.insert_resource(Talon::from_deck(deck));
+    commands.insert_resource(Stack::new(rules.current()));
+}
+
+fn escalate(
+    settings: Res<DifficultySettings>,
+    stack: Res<Stack>,
+    mut escalation: ResMut<Escalation>,
+    mut spawn_timer: ResMut<SpawnTimer>,
+) {
+    if !stack.is_changed() {
+        return;
+    }
+    *escalation = settings.escalation(stack.progress());
+    spawn_timer.0.set_duration(std::time::Duration::from_secs_f32(escalation.spawn_interval));
+}
+
+pub fn collision(
+    mut collision_events: EventReader<CollisionEvent>,
+    mut state: ResMut<NextState<GameState>>,
+    mut talon:ResMut<Talon>,
+    mut stack: ResMut<Stack>,
+    mut score: ResMut<Score>,
+    rules: Res<Rules>,
+    tweening: Res<CardTweening>,
+    query_card: Query<(Entity, &Transform, &Card, Option<&Decoy>), Without<Laser>>,
+    query_laser: Query<(Entity, Option<&Piercing>), With<Laser>>,
+    mut event_hit: EventWriter<HitEvent>,
+    mut powerups: Option<ResMut<PowerUps>>,
+    mut event_sound: EventWriter<SoundEvent>,
+    mut commands: Commands
+){
+    for e in collision_events.iter(){
+        //println!("Collision");
+        let mut remove_card_from_stack:Option<PlayingCard> = None;
+        for (entity_card, card_transform, card, decoy) in query_card.iter() {
+            match e {
+                CollisionEvent::Started(e1, e2, _) => {
+                    if e1 == &entity_card || e2 == &entity_card {
+                        for (entity_laser, piercing) in query_laser.iter() {
+                            if e1 == &entity_laser || e2 == &entity_laser {
+                                let right = decoy.is_none() && stack.accepts(rules.current(), &card.face);
+                                if piercing.is_some() && !right {
+                                    continue;
+                                }
+                                commands.entity(entity_laser).despawn_recursive();
+                                if right {
+                                    event_sound.send(SoundEvent(SoundEffect::Hit));
+                                    event_hit.send(HitEvent {
+                                        position: card_transform.translation,
+                                        outcome: HitOutcome::Correct,
+                                    });
+                                    let (pile, index) = stack.place(rules.current(), card.face);
+                                    commands.entity(entity_card)
+                                        .remove::<RigidBody>()
+                                        .remove::<Collider>()
+                                        .insert(tweening.place(*card_transform,
+                                                               card_slot(stack.piles.len(), pile, index)));
+                                    if stack.is_complete() {
+                                        state.set(GameState::GameOver);
+                                    }
+                                } else {
+                                    event_sound.send(SoundEvent(SoundEffect::Miss));
+                                    if decoy.is_none() {
+                                        talon.cards.push(card.face);
+                                    }
+                                    commands.entity(entity_card).despawn_recursive();
+                                    let shielded = powerups.as_mut().is_some_and(|powerups| powerups.use_shield());
+                                    event_hit.send(HitEvent {
+                                        position: card_transform.translation,
+                                        outcome: if shielded { HitOutcome::Shielded } else { HitOutcome::Wrong },
+                                    });
+                                    if !shielded {
+                                        score.mistakes += 1;
+                                        remove_card_from_stack = stack.take_back();
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+                CollisionEvent::Stopped(_, _, _) => {}
+            }
+        }
+        if let Some(removed) = remove_card_from_stack {
+            for (entity_card, card_transform, card, decoy) in query_card.iter() {
+                if  card.face == removed && decoy.is_none() {
+                    // the card visibly leaves the stack, back up to where the cards come from
+                    commands.entity(entity_card)
+                        .remove::<Card>()
+                        .insert(tweening.remove(*card_transform, card_spawn_point(0.0)));
+                    talon.cards.push(removed)
+                }
+            }
+        }
+    }
+}
+
+/// Width and height of a card.
+pub const CARD_SIZE: f32 = 3.2;
+
+/// Where a card enters the play field, `x` is the sideways offset.
+pub fn card_spawn_point(x: f32) -> Vec3 {
+    SHIP_POSTION + Vec3::new(x, 10.0, -16.0)
+}
+
+/// Physics of a card falling through the play field.
+pub fn falling_card(gravity_scale: f32) -> impl Bundle {
+    (RigidBody::Dynamic,
+     Sleeping::disabled(),
+     Collider::cuboid(CARD_SIZE / 2.0 - 0.5, CARD_SIZE / 2.0, 0.4),
+     ActiveEvents::COLLISION_EVENTS,
+     GravityScale(gravity_scale))
+}
+
+pub fn spawn_card(
+    mut commands: Commands,
+    mut talon:ResMut<Talon>,
+    mut game_rng: ResMut<GameRng>,
+    frame_time: Res<FrameTime>,
+    settings: Res<DifficultySettings>,
+    escalation: Res<Escalation>,
+    stack: Res<Stack>,
+    mut spawn_timer: ResMut<SpawnTimer>,
+    recorder: Option<ResMut<ReplayRecorder>>,
+    player: Option<ResMut<ReplayPlayer>>,
+    asset_server: Res<AssetServer>,
+    mut assets: ResMut<GameAssets>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    if !spawn_timer.0.tick(frame_time.world_delta).just_finished() || talon.cards.is_empty() {
+        return;
+    }
+    let rng = &mut game_rng.rng;
+    // without a ramp no extra number is drawn, older replays stay in sync
+    let decoy = escalation.decoy_chance > 0.0
+        && !stack.texts.is_empty()
+        && rng.gen::<f32>() < escalation.decoy_chance;
+    let card_face = if decoy {
+        stack.texts[rng.gen_range(0..stack.texts.len())]
+    } else {
+        let card_id = rng.gen_range(0..talon.cards.len());
+        talon.cards.remove(card_id)
+    };
+    let x = rng.gen_range(-settings.card_limit_x..settings.card_limit_x);
+
+    if let Some(mut recorder) = recorder {
+        recorder.record(ReplayEvent::Spawn { card: card_face.texture_name(), x });
+    }
+    if let Some(mut player) = player {
+        if !player.check_spawn(&card_face.texture_name(), x) {
+            warn!("replay out of sync at frame {}: spawned {}", player.frame, card_face);
+        }
+    }
+
+    let (mesh, material) = assets.card_look(&card_face.texture_path(), Color::WHITE,
+                                            &asset_server, &mut materials);
+    let card = commands.spawn(PbrBundle {
+        mesh,
+        material,
+        transform: Transform::from_translation(card_spawn_point(x)),
+        ..Default::default()
+    })
+        .insert(falling_card(escalation.gravity_scale))
+        .insert(Card{face:card_face})
+        .id();
+    if decoy {
+        commands.entity(card).insert(Decoy);
+    }
+}
+
+/// Cards below this height are out of the play field.
+pub const CARD_LIMIT_Y:f32=-20.0;
+
+fn despawn_card(
+    mut commands: Commands,
+    mut talon:ResMut<Talon>,
+    mut query_card: Query<(Entity,&mut Transform, &Card, Option<&Decoy>)>,
+    mut event_sound: EventWriter<SoundEvent>,
+) {
+    for (e, transform, card, decoy) in query_card.iter_mut(){
+        if transform.translation.y <=CARD_LIMIT_Y {
+            if decoy.is_none() {
+                talon.cards.push(card.face);
+                event_sound.send(SoundEvent(SoundEffect::CardLost));
+            }
+            commands.entity(e).despawn_recursive();
+        }
+    }
+}
+
+fn clear_cards(
+    mut commands: Commands,
+    query: Query<Entity, Or<(With<Card>, With<Tween>)>>
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    commands.remove_resource::<SpawnTimer>();
+    commands.remove_resource::<Escalation>();
+    commands.remove_resource::<Talon>();
+    commands.remove_resource::<Stack>();
+}
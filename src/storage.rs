@@ -0,0 +1,38 @@
+//! Small key/value store for data kept between runs: a file per key on native
+//! builds, browser local storage on wasm.
+
+#[cfg(not(target_arch = "wasm32"))]
+const SAVE_DIR: &str = "saves";
+
+#[cfg(target_arch = "wasm32")]
+const STORAGE_PREFIX: &str = "bevy-air-ace.";
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn load(key: &str) -> Option<String> {
+    std::fs::read_to_string(std::path::Path::new(SAVE_DIR).join(format!("{}.json", key))).ok()
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+pub fn save(key: &str, value: &str) -> Result<(), String> {
+    std::fs::create_dir_all(SAVE_DIR).map_err(|e| e.to_string())?;
+    std::fs::write(std::path::Path::new(SAVE_DIR).join(format!("{}.json", key)), value)
+        .map_err(|e| e.to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Option<web_sys::Storage> {
+    web_sys::window()?.local_storage().ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn load(key: &str) -> Option<String> {
+    local_storage()?.get_item(&format!("{}{}", STORAGE_PREFIX, key)).ok()?
+}
+
+#[cfg(target_arch = "wasm32")]
+pub fn save(key: &str, value: &str) -> Result<(), String> {
+    local_storage()
+        .ok_or_else(|| "no local storage available".to_string())?
+        .set_item(&format!("{}{}", STORAGE_PREFIX, key), value)
+        .map_err(|e| format!("{:?}", e))
+}
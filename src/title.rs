@@ -0,0 +1,347 @@
+use bevy::prelude::*;
+
+use crate::audio::AudioSettings;
+use crate::controls::{Action, Actions, InputMap};
+use crate::deck::{Deck, DeckHandle};
+use crate::difficulty::Difficulty;
+use crate::game::GameState;
+use crate::highscore::HighScores;
+use crate::replay::ReplayPlayer;
+use crate::rules::Rules;
+use crate::ship::FlightMode;
+
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+enum TitlePage {
+    #[default]
+    Main,
+    HighScores,
+    Settings,
+    Credits,
+}
+
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+enum MenuButton {
+    Play,
+    Mode,
+    Difficulty,
+    HighScores,
+    Settings,
+    Credits,
+    Flight,
+    MasterVolume,
+    MusicVolume,
+    EffectsVolume,
+    Mute,
+    Controls,
+    Back,
+}
+
+impl TitlePage {
+    fn buttons(&self) -> &'static [MenuButton] {
+        match self {
+            TitlePage::Main => &[MenuButton::Play, MenuButton::Mode, MenuButton::Difficulty,
+                                 MenuButton::HighScores, MenuButton::Settings, MenuButton::Credits],
+            TitlePage::Settings => &[MenuButton::Flight, MenuButton::MasterVolume, MenuButton::MusicVolume,
+                                     MenuButton::EffectsVolume, MenuButton::Mute, MenuButton::Controls,
+                                     MenuButton::Back],
+            TitlePage::HighScores | TitlePage::Credits => &[MenuButton::Back],
+        }
+    }
+}
+
+/// Page and button the title menu is on, kept while playing so a run returns to it.
+#[derive(Resource, Default)]
+struct TitleMenu {
+    page: TitlePage,
+    selected: usize,
+}
+
+/// Root node of the title screen, built for one page.
+#[derive(Component)]
+struct Titlescreen(TitlePage);
+
+#[derive(Component)]
+struct MenuEntry {
+    button: MenuButton,
+    index: usize,
+}
+
+#[derive(Component)]
+struct ButtonLabel(MenuButton);
+
+/// A button was pressed, `step` is -1 when a value is cycled backwards.
+struct MenuActivated {
+    button: MenuButton,
+    step: i32,
+}
+
+const TEXT_COLOR: Color = Color::rgb(0.5, 0.5, 1.0);
+const LABEL_COLOR: Color = Color::rgb(0.9, 0.9, 1.0);
+const BUTTON_COLOR: Color = Color::rgb(0.12, 0.12, 0.3);
+const SELECTED_BUTTON_COLOR: Color = Color::rgb(0.3, 0.3, 0.7);
+
+/// Title screen menu, driven by mouse, keyboard and gamepad alike.
+pub struct TitlePlugin;
+
+impl Plugin for TitlePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<TitleMenu>()
+            .add_event::<MenuActivated>()
+            .add_system(spawn_title.in_schedule(OnEnter(GameState::GameStart)))
+            .add_systems((title_navigation, title_activate, rebuild_title, update_buttons)
+                             .chain()
+                             .in_set(OnUpdate(GameState::GameStart)))
+            .add_system(start_replay.in_set(OnUpdate(GameState::GameStart)))
+            .add_system(exit_title.in_schedule(OnExit(GameState::GameStart)));
+    }
+}
+
+fn spawn_title(
+    mut commands: Commands,
+    menu: Res<TitleMenu>,
+    highscores: Res<HighScores>,
+    rules: Res<Rules>,
+    difficulty: Res<Difficulty>,
+    input_map: Res<InputMap>,
+    asset_server: Res<AssetServer>,
+) {
+    let font = asset_server.load("fonts/FiraSans-Bold.ttf");
+    let text_style = |font_size: f32, color: Color| TextStyle {
+        font: font.clone(),
+        font_size,
+        color,
+    };
+    let content = match menu.page {
+        TitlePage::Main | TitlePage::Settings => None,
+        TitlePage::HighScores => Some(highscores.table(rules.current().name(), *difficulty)),
+        TitlePage::Credits => Some(" by sabi@nelson-games.de \n \
+                                    \n \
+                                    assets by Kenney https://www.kenney.nl \n \
+                                    made with rust and bevy ".to_string()),
+    };
+    let hint = format!("{} / {}: choose   {} / {}: change   {}: select   {}: back",
+                       input_map.describe(Action::MenuUp), input_map.describe(Action::MenuDown),
+                       input_map.describe(Action::MenuLeft), input_map.describe(Action::MenuRight),
+                       input_map.describe(Action::Confirm), input_map.describe(Action::Back));
+
+    commands.spawn(NodeBundle {
+        style: Style {
+            size: Size::new(Val::Percent(100.0), Val::Percent(100.0)),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            justify_content: JustifyContent::Center,
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+        .insert(Titlescreen(menu.page))
+        .with_children(|parent| {
+            parent.spawn(TextBundle::from_section("Bevy Air Ace", text_style(64.0, TEXT_COLOR))
+                .with_style(Style {
+                    margin: UiRect::bottom(Val::Px(20.0)),
+                    ..Default::default()
+                }));
+            if let Some(content) = content {
+                parent.spawn(TextBundle::from_section(content, text_style(20.0, TEXT_COLOR))
+                    .with_style(Style {
+                        margin: UiRect::bottom(Val::Px(20.0)),
+                        ..Default::default()
+                    }));
+            }
+            for (index, button) in menu.page.buttons().iter().enumerate() {
+                parent.spawn(ButtonBundle {
+                    style: Style {
+                        size: Size::new(Val::Px(380.0), Val::Px(48.0)),
+                        margin: UiRect::all(Val::Px(5.0)),
+                        justify_content: JustifyContent::Center,
+                        align_items: AlignItems::Center,
+                        ..Default::default()
+                    },
+                    background_color: BUTTON_COLOR.into(),
+                    ..Default::default()
+                })
+                    .insert(MenuEntry { button: *button, index })
+                    .with_children(|parent| {
+                        parent.spawn(TextBundle::from_section("", text_style(32.0, LABEL_COLOR)))
+                            .insert(ButtonLabel(*button));
+                    });
+            }
+            parent.spawn(TextBundle::from_section(hint, text_style(18.0, TEXT_COLOR))
+                .with_style(Style {
+                    margin: UiRect::top(Val::Px(20.0)),
+                    ..Default::default()
+                }));
+        });
+}
+
+fn title_navigation(
+    actions: Actions,
+    mut menu: ResMut<TitleMenu>,
+    mut game_state: ResMut<NextState<GameState>>,
+    interactions: Query<(&Interaction, &MenuEntry), Changed<Interaction>>,
+    mut activated: EventWriter<MenuActivated>,
+) {
+    let buttons = menu.page.buttons();
+    for (interaction, entry) in interactions.iter() {
+        match interaction {
+            Interaction::Hovered => menu.selected = entry.index,
+            Interaction::Clicked => {
+                menu.selected = entry.index;
+                activated.send(MenuActivated { button: entry.button, step: 1 });
+            }
+            Interaction::None => {}
+        }
+    }
+
+    let selected = buttons[menu.selected.min(buttons.len() - 1)];
+    // left and right only cycle values, they never open a page
+    let cycles = matches!(selected, MenuButton::Mode | MenuButton::Difficulty | MenuButton::Flight
+        | MenuButton::MasterVolume | MenuButton::MusicVolume | MenuButton::EffectsVolume | MenuButton::Mute);
+    if actions.just_pressed(Action::MenuUp) {
+        menu.selected = (menu.selected + buttons.len() - 1) % buttons.len();
+    } else if actions.just_pressed(Action::MenuDown) {
+        menu.selected = (menu.selected + 1) % buttons.len();
+    } else if cycles && actions.just_pressed(Action::MenuLeft) {
+        activated.send(MenuActivated { button: selected, step: -1 });
+    } else if (cycles && actions.just_pressed(Action::MenuRight)) || actions.just_pressed(Action::Confirm) {
+        activated.send(MenuActivated { button: selected, step: 1 });
+    } else if actions.just_pressed(Action::Back) && menu.page != TitlePage::Main {
+        activated.send(MenuActivated { button: MenuButton::Back, step: 1 });
+    } else if actions.just_pressed(Action::Controls) {
+        game_state.set(GameState::Controls);
+    }
+}
+
+fn title_activate(
+    mut activated: EventReader<MenuActivated>,
+    mut menu: ResMut<TitleMenu>,
+    mut rules: ResMut<Rules>,
+    mut difficulty: ResMut<Difficulty>,
+    mut flight_mode: ResMut<FlightMode>,
+    mut audio: ResMut<AudioSettings>,
+    mut game_state: ResMut<NextState<GameState>>,
+    decks: Res<Assets<Deck>>,
+    deck: Res<DeckHandle>,
+) {
+    for MenuActivated { button, step } in activated.iter() {
+        let open = |menu: &mut TitleMenu, page: TitlePage| {
+            menu.page = page;
+            menu.selected = 0;
+        };
+        match button {
+            MenuButton::Play => {
+                if decks.contains(&deck.0) {
+                    game_state.set(GameState::Playing);
+                }
+            }
+            MenuButton::Mode => {
+                if *step < 0 {
+                    rules.select_previous();
+                } else {
+                    rules.select_next();
+                }
+            }
+            MenuButton::Difficulty => {
+                *difficulty = if *step < 0 { difficulty.previous() } else { difficulty.next() };
+            }
+            MenuButton::Flight => {
+                *flight_mode = if *step < 0 { flight_mode.previous() } else { flight_mode.next() };
+            }
+            MenuButton::MasterVolume => AudioSettings::change(&mut audio.master, *step),
+            MenuButton::MusicVolume => AudioSettings::change(&mut audio.music, *step),
+            MenuButton::EffectsVolume => AudioSettings::change(&mut audio.effects, *step),
+            MenuButton::Mute => audio.muted = !audio.muted,
+            MenuButton::HighScores => open(&mut menu, TitlePage::HighScores),
+            MenuButton::Settings => open(&mut menu, TitlePage::Settings),
+            MenuButton::Credits => open(&mut menu, TitlePage::Credits),
+            MenuButton::Controls => game_state.set(GameState::Controls),
+            MenuButton::Back => {
+                // come back to the button the page was opened with
+                let from = match menu.page {
+                    TitlePage::HighScores => MenuButton::HighScores,
+                    TitlePage::Settings => MenuButton::Settings,
+                    TitlePage::Credits => MenuButton::Credits,
+                    TitlePage::Main => MenuButton::Play,
+                };
+                menu.page = TitlePage::Main;
+                menu.selected = TitlePage::Main.buttons().iter().position(|b| *b == from).unwrap_or(0);
+            }
+        }
+    }
+}
+
+fn rebuild_title(
+    mut commands: Commands,
+    menu: Res<TitleMenu>,
+    highscores: Res<HighScores>,
+    rules: Res<Rules>,
+    difficulty: Res<Difficulty>,
+    input_map: Res<InputMap>,
+    asset_server: Res<AssetServer>,
+    query: Query<(Entity, &Titlescreen)>,
+) {
+    if !query.iter().any(|(_, screen)| screen.0 != menu.page) {
+        return;
+    }
+    for (entity, _) in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    spawn_title(commands, menu, highscores, rules, difficulty, input_map, asset_server);
+}
+
+fn button_label(button: MenuButton, rules: &Rules, difficulty: Difficulty, flight_mode: FlightMode,
+                audio: &AudioSettings) -> String {
+    let volume = |volume: f32| format!("< {:.0}% >", volume * 100.0);
+    match button {
+        MenuButton::Play => "play".to_string(),
+        MenuButton::Mode => format!("< {} >", rules.current().name()),
+        MenuButton::Difficulty => format!("< {} >", difficulty),
+        MenuButton::HighScores => "high scores".to_string(),
+        MenuButton::Settings => "settings".to_string(),
+        MenuButton::Credits => "credits".to_string(),
+        MenuButton::Flight => format!("ship: < {} >", flight_mode),
+        MenuButton::MasterVolume => format!("volume: {}", volume(audio.master)),
+        MenuButton::MusicVolume => format!("music: {}", volume(audio.music)),
+        MenuButton::EffectsVolume => format!("effects: {}", volume(audio.effects)),
+        MenuButton::Mute => format!("sound: < {} >", if audio.muted { "off" } else { "on" }),
+        MenuButton::Controls => "controls".to_string(),
+        MenuButton::Back => "back".to_string(),
+    }
+}
+
+fn update_buttons(
+    menu: Res<TitleMenu>,
+    rules: Res<Rules>,
+    difficulty: Res<Difficulty>,
+    flight_mode: Res<FlightMode>,
+    audio: Res<AudioSettings>,
+    mut buttons: Query<(&MenuEntry, &mut BackgroundColor)>,
+    mut labels: Query<(&ButtonLabel, &mut Text)>,
+) {
+    for (entry, mut color) in buttons.iter_mut() {
+        *color = if entry.index == menu.selected { SELECTED_BUTTON_COLOR } else { BUTTON_COLOR }.into();
+    }
+    for (label, mut text) in labels.iter_mut() {
+        text.sections[0].value = button_label(label.0, &rules, *difficulty, *flight_mode, &audio);
+    }
+}
+
+fn start_replay(
+    mut game_state: ResMut<NextState<GameState>>,
+    decks: Res<Assets<Deck>>,
+    deck: Res<DeckHandle>,
+    player: Option<Res<ReplayPlayer>>,
+) {
+    if player.is_some() && decks.contains(&deck.0) {
+        game_state.set(GameState::Playing);
+    }
+}
+
+fn exit_title(
+    mut commands: Commands,
+    query: Query<Entity, With<Titlescreen>>
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
@@ -0,0 +1,188 @@
+//! Transform animations with easing, used to fly cards onto the stack and off it.
+
+use bevy::prelude::*;
+
+use crate::game::{FrameTime, GameState};
+
+/// How the progress of a tween speeds up and slows down.
+#[derive(Debug, Clone, Copy, Default, Eq, PartialEq)]
+pub enum Ease {
+    Linear,
+    /// Starts slow, ends fast.
+    QuadIn,
+    /// Starts fast, ends slow.
+    #[default]
+    CubicOut,
+    /// Overshoots a little and settles back.
+    BackOut,
+}
+
+impl Ease {
+    /// Eased progress for the linear progress `t` from 0 to 1.
+    pub fn apply(&self, t: f32) -> f32 {
+        let t = t.clamp(0.0, 1.0);
+        match self {
+            Ease::Linear => t,
+            Ease::QuadIn => t * t,
+            Ease::CubicOut => 1.0 - (1.0 - t).powi(3),
+            Ease::BackOut => {
+                const OVERSHOOT: f32 = 1.7;
+                let t = t - 1.0;
+                1.0 + t * t * ((OVERSHOOT + 1.0) * t + OVERSHOOT)
+            }
+        }
+    }
+}
+
+/// Moves a transform from `from` to `to` along an arc, easing translation, rotation and scale.
+#[derive(Component, Debug, Clone)]
+pub struct Tween {
+    pub from: Transform,
+    pub to: Transform,
+    /// Seconds from start to end.
+    pub duration: f32,
+    pub ease: Ease,
+    /// Height of the arc above the straight line, at its middle.
+    pub arc: f32,
+    /// Extra turn around the card's face, in radians, unwound on the way.
+    pub spin: f32,
+    /// Despawns the entity when the tween is done, instead of just removing the tween.
+    pub despawn: bool,
+    elapsed: f32,
+}
+
+impl Tween {
+    pub fn new(from: Transform, to: Transform, duration: f32) -> Self {
+        Self {
+            from,
+            to,
+            duration,
+            ease: Ease::default(),
+            arc: 0.0,
+            spin: 0.0,
+            despawn: false,
+            elapsed: 0.0,
+        }
+    }
+
+    pub fn with_ease(mut self, ease: Ease) -> Self {
+        self.ease = ease;
+        self
+    }
+
+    pub fn with_arc(mut self, arc: f32) -> Self {
+        self.arc = arc;
+        self
+    }
+
+    pub fn with_spin(mut self, spin: f32) -> Self {
+        self.spin = spin;
+        self
+    }
+
+    pub fn despawn_when_done(mut self) -> Self {
+        self.despawn = true;
+        self
+    }
+
+    pub fn is_done(&self) -> bool {
+        self.elapsed >= self.duration
+    }
+
+    /// Transform after `dt` more seconds.
+    pub fn advance(&mut self, dt: f32) -> Transform {
+        self.elapsed = (self.elapsed + dt).min(self.duration);
+        let t = if self.duration > 0.0 { self.elapsed / self.duration } else { 1.0 };
+        let e = self.ease.apply(t);
+        // a quadratic curve through a point raised above the middle of the way
+        let control = self.from.translation.lerp(self.to.translation, 0.5) + Vec3::Y * self.arc * 2.0;
+        let translation = self.from.translation.lerp(control, e)
+            .lerp(control.lerp(self.to.translation, e), e);
+        let rotation = self.from.rotation.slerp(self.to.rotation, e.clamp(0.0, 1.0))
+            * Quat::from_rotation_z(self.spin * (1.0 - e));
+        let scale = self.from.scale.lerp(self.to.scale, e);
+        Transform { translation, rotation, scale }
+    }
+}
+
+/// How cards fly onto the stack and off it again.
+#[derive(Resource, Debug, Clone)]
+pub struct CardTweening {
+    /// Seconds a hit card flies into its slot.
+    pub place_duration: f32,
+    /// Seconds a card taken off the stack flies back up.
+    pub remove_duration: f32,
+}
+
+impl Default for CardTweening {
+    fn default() -> Self {
+        Self {
+            place_duration: 0.5,
+            remove_duration: 0.7,
+        }
+    }
+}
+
+impl CardTweening {
+    /// Flight of a card from where it was hit into its slot on the whiteboard.
+    pub fn place(&self, from: Transform, slot: Vec3) -> Tween {
+        Tween::new(from, Transform::from_translation(slot), self.place_duration)
+            .with_ease(Ease::BackOut)
+            .with_arc(2.0)
+            .with_spin(std::f32::consts::TAU)
+    }
+
+    /// Flight of a card off the stack to `to`, where it disappears.
+    pub fn remove(&self, from: Transform, to: Vec3) -> Tween {
+        Tween::new(from, Transform::from_translation(to).with_scale(Vec3::splat(0.4)), self.remove_duration)
+            .with_ease(Ease::QuadIn)
+            .with_arc(3.0)
+            .with_spin(-std::f32::consts::TAU)
+            .despawn_when_done()
+    }
+}
+
+/// Plays [`Tween`]s during a run, on the world time, so they slow down with the
+/// game and follow the frames of a replay. The game over screen shows them finished.
+pub struct TweenPlugin;
+
+impl Plugin for TweenPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_system(tween.in_set(OnUpdate(GameState::Playing)))
+            .add_system(finish_tweens.in_schedule(OnEnter(GameState::GameOver)));
+    }
+}
+
+fn end_tween(commands: &mut Commands, entity: Entity, tween: &Tween) {
+    if tween.despawn {
+        commands.entity(entity).despawn_recursive();
+    } else {
+        commands.entity(entity).remove::<Tween>();
+    }
+}
+
+fn tween(
+    mut commands: Commands,
+    frame_time: Res<FrameTime>,
+    mut query: Query<(Entity, &mut Transform, &mut Tween)>,
+) {
+    let dt = frame_time.world_delta.as_secs_f32();
+    for (entity, mut transform, mut tween) in query.iter_mut() {
+        *transform = tween.advance(dt);
+        if tween.is_done() {
+            end_tween(&mut commands, entity, &tween);
+        }
+    }
+}
+
+/// Puts every card still flying where it was going, e.g. the one completing the stack.
+fn finish_tweens(
+    mut commands: Commands,
+    mut query: Query<(Entity, &mut Transform, &mut Tween)>,
+) {
+    for (entity, mut transform, mut tween) in query.iter_mut() {
+        let duration = tween.duration;
+        *transform = tween.advance(duration);
+        end_tween(&mut commands, entity, &tween);
+    }
+}
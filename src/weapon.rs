@@ -0,0 +1,379 @@
+use bevy::prelude::*;
+use bevy_rapier3d::prelude::*;
+
+use crate::game::{EndRun, FrameTime, GameState};
+use crate::rules::{Rules, Stack};
+use crate::ship::spawn_laser;
+use crate::solitaire::{Card, Decoy};
+
+/// How a shot looks and behaves once it left the ship.
+#[derive(Debug, Clone, Copy, Eq, PartialEq)]
+pub enum ShotKind {
+    Laser,
+    /// Flies through wrong cards and decoys, only the right card stops it.
+    Beam,
+    /// Curves towards the card that has to be shot next.
+    Missile,
+}
+
+impl ShotKind {
+    pub const ALL: [ShotKind; 3] = [ShotKind::Laser, ShotKind::Beam, ShotKind::Missile];
+
+    /// Width, height and length of the shot.
+    pub fn size(&self) -> Vec3 {
+        match self {
+            ShotKind::Laser => Vec3::new(0.1, 0.1, 1.6),
+            ShotKind::Beam => Vec3::new(0.15, 0.15, 4.0),
+            ShotKind::Missile => Vec3::new(0.3, 0.3, 0.8),
+        }
+    }
+
+    pub fn color(&self) -> Color {
+        match self {
+            ShotKind::Laser => Color::LIME_GREEN,
+            ShotKind::Beam => Color::CYAN,
+            ShotKind::Missile => Color::ORANGE_RED,
+        }
+    }
+}
+
+/// A single projectile leaving the ship.
+#[derive(Debug, Clone, Copy)]
+pub struct Shot {
+    pub kind: ShotKind,
+    pub direction: Vec3,
+    pub speed: f32,
+}
+
+/// A gun the ship can carry: what a pull of the trigger fires and what it costs.
+pub trait WeaponKind: Send + Sync + 'static {
+    fn name(&self) -> &'static str;
+
+    /// Share of the stack that has to be placed before the weapon can be picked.
+    fn unlocks_at(&self) -> f32 {
+        0.0
+    }
+
+    /// Heat of a trigger pull, relative to a single laser.
+    fn heat_factor(&self) -> f32 {
+        1.0
+    }
+
+    /// Time until the next trigger pull, relative to a single laser.
+    fn reload_factor(&self) -> f32 {
+        1.0
+    }
+
+    /// Shots of a trigger pull, `aim` is the direction from the ship to the target.
+    fn shots(&self, aim: Vec3) -> Vec<Shot>;
+}
+
+pub struct SingleLaser;
+
+impl WeaponKind for SingleLaser {
+    fn name(&self) -> &'static str {
+        "laser"
+    }
+
+    fn shots(&self, aim: Vec3) -> Vec<Shot> {
+        vec![Shot { kind: ShotKind::Laser, direction: aim, speed: 84.0 }]
+    }
+}
+
+pub struct SpreadShot;
+
+const SPREAD_ANGLE: f32 = 0.12;
+
+impl WeaponKind for SpreadShot {
+    fn name(&self) -> &'static str {
+        "spread"
+    }
+
+    fn unlocks_at(&self) -> f32 {
+        0.25
+    }
+
+    fn heat_factor(&self) -> f32 {
+        2.0
+    }
+
+    fn shots(&self, aim: Vec3) -> Vec<Shot> {
+        [-SPREAD_ANGLE, 0.0, SPREAD_ANGLE].into_iter()
+            .map(|angle| Shot {
+                kind: ShotKind::Laser,
+                direction: Quat::from_rotation_y(angle) * aim,
+                speed: 84.0,
+            })
+            .collect()
+    }
+}
+
+pub struct PiercingBeam;
+
+impl WeaponKind for PiercingBeam {
+    fn name(&self) -> &'static str {
+        "beam"
+    }
+
+    fn unlocks_at(&self) -> f32 {
+        0.5
+    }
+
+    fn heat_factor(&self) -> f32 {
+        2.5
+    }
+
+    fn reload_factor(&self) -> f32 {
+        2.0
+    }
+
+    fn shots(&self, aim: Vec3) -> Vec<Shot> {
+        vec![Shot { kind: ShotKind::Beam, direction: aim, speed: 120.0 }]
+    }
+}
+
+pub struct HomingMissile;
+
+impl WeaponKind for HomingMissile {
+    fn name(&self) -> &'static str {
+        "missile"
+    }
+
+    fn unlocks_at(&self) -> f32 {
+        0.75
+    }
+
+    fn heat_factor(&self) -> f32 {
+        3.0
+    }
+
+    fn reload_factor(&self) -> f32 {
+        4.0
+    }
+
+    fn shots(&self, aim: Vec3) -> Vec<Shot> {
+        vec![Shot { kind: ShotKind::Missile, direction: aim, speed: 30.0 }]
+    }
+}
+
+/// The weapons of the ship, the selected one and how many the run has unlocked.
+#[derive(Resource)]
+pub struct Arsenal {
+    kinds: Vec<Box<dyn WeaponKind>>,
+    selected: usize,
+    /// Highest share of the stack placed during the run.
+    progress: f32,
+}
+
+impl Arsenal {
+    pub fn add(&mut self, kind: impl WeaponKind) {
+        self.kinds.push(Box::new(kind));
+    }
+
+    pub fn current(&self) -> &dyn WeaponKind {
+        self.kinds[self.selected].as_ref()
+    }
+
+    /// The weapon with the given name, the first one if there is none.
+    pub fn by_name(&self, name: &str) -> &dyn WeaponKind {
+        self.kinds.iter()
+            .find(|kind| kind.name() == name)
+            .unwrap_or(&self.kinds[0])
+            .as_ref()
+    }
+
+    pub fn is_unlocked(&self, kind: &dyn WeaponKind) -> bool {
+        kind.unlocks_at() <= self.progress
+    }
+
+    /// Unlocks the weapons up to the given share of the stack, kept when cards are taken back.
+    pub fn unlock(&mut self, progress: f32) {
+        self.progress = self.progress.max(progress);
+    }
+
+    pub fn select_by_name(&mut self, name: &str) -> bool {
+        match self.kinds.iter().position(|kind| kind.name() == name) {
+            Some(index) => {
+                self.selected = index;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Switches to the next unlocked weapon.
+    pub fn select_next(&mut self) {
+        for step in 1..=self.kinds.len() {
+            let index = (self.selected + step) % self.kinds.len();
+            if self.is_unlocked(self.kinds[index].as_ref()) {
+                self.selected = index;
+                return;
+            }
+        }
+    }
+
+    fn reset(&mut self) {
+        self.selected = 0;
+        self.progress = 0.0;
+    }
+}
+
+impl Default for Arsenal {
+    fn default() -> Self {
+        Self {
+            kinds: vec![Box::new(SingleLaser), Box::new(SpreadShot),
+                        Box::new(PiercingBeam), Box::new(HomingMissile)],
+            selected: 0,
+            progress: 0.0,
+        }
+    }
+}
+
+/// The ship's gun: it fires at a fixed rate while the trigger is held and heats
+/// up with every shot. At full heat it overheats and stays locked until cooled down.
+#[derive(Resource, Debug, Clone)]
+pub struct Weapon {
+    /// Seconds between two shots of a single laser.
+    pub fire_interval: f32,
+    /// Heat added by a single laser, the gun overheats at 1.
+    pub heat_per_shot: f32,
+    /// Heat lost per second.
+    pub cooling: f32,
+    /// Seconds an overheated gun stays locked.
+    pub overheat_time: f32,
+    pub heat: f32,
+    reload: f32,
+    locked: f32,
+}
+
+impl Default for Weapon {
+    fn default() -> Self {
+        Self {
+            fire_interval: 0.15,
+            heat_per_shot: 0.12,
+            cooling: 0.45,
+            overheat_time: 1.5,
+            heat: 0.0,
+            reload: 0.0,
+            locked: 0.0,
+        }
+    }
+}
+
+impl Weapon {
+    pub fn ready(&self) -> bool {
+        self.reload <= 0.0 && !self.is_overheated()
+    }
+
+    pub fn is_overheated(&self) -> bool {
+        self.locked > 0.0
+    }
+
+    /// Heats the gun up by a trigger pull of `kind`, locking it when it reaches full heat.
+    pub fn shoot(&mut self, kind: &dyn WeaponKind) {
+        self.reload = self.fire_interval * kind.reload_factor();
+        self.heat = (self.heat + self.heat_per_shot * kind.heat_factor()).min(1.0);
+        if self.heat >= 1.0 {
+            self.locked = self.overheat_time;
+        }
+    }
+
+    /// Cools the gun down over `dt` seconds.
+    pub fn cool(&mut self, dt: f32) {
+        self.reload = (self.reload - dt).max(0.0);
+        self.heat = (self.heat - self.cooling * dt).max(0.0);
+        if self.is_overheated() {
+            self.locked = (self.locked - dt).max(0.0);
+            // the gauge runs down with the lock, so it is free again when empty
+            self.heat = self.heat.min(self.locked / self.overheat_time);
+        }
+    }
+
+    /// Back to a cold gun, keeping the tuning.
+    pub fn reset(&mut self) {
+        self.heat = 0.0;
+        self.reload = 0.0;
+        self.locked = 0.0;
+    }
+}
+
+/// Marks a shot flying through wrong cards.
+#[derive(Component)]
+pub struct Piercing;
+
+/// A missile, turning towards the next card to shoot.
+#[derive(Component)]
+pub struct Homing {
+    /// Radians per second.
+    pub turn_rate: f32,
+}
+
+/// Gun heat, the weapon kinds and their unlocks, and homing missiles.
+pub struct WeaponPlugin;
+
+impl Plugin for WeaponPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<Weapon>()
+            .init_resource::<Arsenal>()
+            .add_systems((cool_weapon, home_missiles)
+                             .after(spawn_laser)
+                             .in_set(OnUpdate(GameState::Playing)))
+            .add_system(unlock_weapons.in_set(OnUpdate(GameState::Playing)))
+            .add_system(reset_weapons.in_schedule(EndRun));
+    }
+}
+
+fn cool_weapon(
+    frame_time: Res<FrameTime>,
+    mut weapon: ResMut<Weapon>,
+) {
+    weapon.cool(frame_time.delta.as_secs_f32());
+}
+
+fn unlock_weapons(
+    stack: Res<Stack>,
+    mut arsenal: ResMut<Arsenal>,
+) {
+    if stack.is_changed() {
+        arsenal.unlock(stack.progress());
+    }
+}
+
+fn home_missiles(
+    frame_time: Res<FrameTime>,
+    rules: Res<Rules>,
+    stack: Res<Stack>,
+    mut query: Query<(&mut Transform, &mut Velocity, &Homing)>,
+    query_card: Query<(&Transform, &Card), (With<RigidBody>, Without<Decoy>, Without<Homing>)>,
+) {
+    let dt = frame_time.world_delta.as_secs_f32();
+    for (mut transform, mut velocity, homing) in query.iter_mut() {
+        let target = query_card.iter()
+            .filter(|(_, card)| stack.accepts(rules.current(), &card.face))
+            .map(|(card_transform, _)| card_transform.translation)
+            .min_by(|a, b| a.distance(transform.translation).total_cmp(&b.distance(transform.translation)));
+        let Some(target) = target else {
+            continue;
+        };
+        let speed = velocity.linvel.length();
+        let heading = velocity.linvel.normalize_or_zero();
+        let wanted = (target - transform.translation).normalize_or_zero();
+        let angle = heading.angle_between(wanted);
+        if angle.is_nan() || angle < f32::EPSILON {
+            continue;
+        }
+        let turn = Quat::from_rotation_arc(heading, wanted);
+        let step = (homing.turn_rate * dt / angle).min(1.0);
+        let direction = Quat::IDENTITY.slerp(turn, step) * heading;
+        velocity.linvel = direction * speed;
+        transform.rotation = Transform::IDENTITY.looking_at(direction, Vec3::Y).rotation;
+    }
+}
+
+fn reset_weapons(
+    mut weapon: ResMut<Weapon>,
+    mut arsenal: ResMut<Arsenal>,
+) {
+    weapon.reset();
+    arsenal.reset();
+}
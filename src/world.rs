@@ -0,0 +1,95 @@
+use bevy::prelude::*;
+use bevy::pbr::NotShadowReceiver;
+use bevy_mod_raycast::{RaycastMesh,RaycastSystem,DefaultRaycastingPlugin,
+                       RaycastSource, RaycastMethod};
+
+use crate::ship::SHIP_POSTION;
+
+/// Ray cast set of the cursor ray, hitting the whiteboard.
+#[derive(Clone, Reflect)]
+pub struct MyRaycastSet;
+
+#[derive(Component)]
+pub struct Camera;
+
+pub const CAMERA_POSITION: Vec3 = Vec3::new(0.0, 1.0, 0.0);
+pub const WHITEBOARD_POSITION: Vec3 = Vec3::new(0.0, 0.0, -30.0);
+pub const WHITEBOARD_SIZE: Vec3 = Vec3::new(40.0, 32.0, 1.0);
+
+/// Camera, light and the whiteboard the cards are laid out on.
+pub struct WorldPlugin;
+
+impl Plugin for WorldPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugin(DefaultRaycastingPlugin::<MyRaycastSet>::default())
+            .add_startup_system(setup_camera)
+            .add_startup_system(setup_scene)
+            .add_system(
+                update_raycast_with_cursor
+                    .in_base_set(CoreSet::First)
+                    .before(RaycastSystem::BuildRays::<MyRaycastSet>),
+            );
+    }
+}
+
+fn setup_camera(
+    mut commands: Commands
+) {
+    commands.
+        spawn(Camera3dBundle {
+            transform: Transform::from_translation(CAMERA_POSITION).looking_at(SHIP_POSTION+Vec3::new(0.0,1.0,0.0), Vec3::Y),
+            ..Default::default()
+        })
+        .insert(UiCameraConfig {
+            show_ui: true,
+            ..default()
+        })
+        .insert(RaycastSource::<MyRaycastSet>::new())
+        .insert(Camera{});
+}
+
+fn setup_scene(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+) {
+    //light
+    commands.spawn(DirectionalLightBundle {
+        directional_light: DirectionalLight {
+            shadows_enabled: true,
+            ..default()
+        },
+        transform: Transform {
+            translation: Vec3::new(0.0, 4.0, 0.0),
+            rotation: Quat::from_rotation_x(-std::f32::consts::FRAC_PI_4),
+            ..default()
+        },
+        ..default()
+    });
+
+    //whiteboard
+    commands
+        .spawn(PbrBundle {
+            mesh: meshes.add(Mesh::from(shape::Box::new(WHITEBOARD_SIZE.x, WHITEBOARD_SIZE.y, WHITEBOARD_SIZE.z))),
+            material: materials.add(Color::rgb(0.9, 0.9, 1.0).into()),
+            transform: Transform::from_translation(WHITEBOARD_POSITION),
+            ..Default::default()
+        })
+        .insert(NotShadowReceiver)
+        .insert(RaycastMesh::<MyRaycastSet>::default()); // Make this mesh ray cast-able
+}
+
+fn update_raycast_with_cursor(
+    mut cursor: EventReader<CursorMoved>,
+    mut query: Query<&mut RaycastSource<MyRaycastSet>>,
+) {
+    // Grab the most recent cursor event if it exists:
+    let cursor_position = match cursor.iter().last() {
+        Some(cursor_moved) => cursor_moved.position,
+        None => return,
+    };
+
+    for mut pick_source in &mut query {
+        pick_source.cast_method = RaycastMethod::Screenspace(cursor_position);
+    }
+}
@@ -0,0 +1,50 @@
+use std::time::Duration;
+use bevy::prelude::*;
+use rust_bevy_air_ace::harness::HeadlessGame;
+use rust_bevy_air_ace::ship::{CreateLaserEvent, Laser, Ship, ShipPlugin};
+use rust_bevy_air_ace::solitaire::SpawnTimer;
+use rust_bevy_air_ace::weapon::{Weapon, WeaponPlugin};
+
+#[test]
+fn asset_counts_stay_bounded_over_a_long_run() {
+    let mut game = HeadlessGame::new(8);
+    game.app.add_plugin(WeaponPlugin)
+        .add_plugin(ShipPlugin::default());
+    game.app.world.spawn((TransformBundle::default(), Ship::default()));
+    game.start();
+
+    let mut counts = Vec::new();
+    let mut shots = 0;
+    for frame in 0..600 {
+        // a card due every frame, and a shot of every weapon
+        let mut timer = game.app.world.resource_mut::<SpawnTimer>();
+        timer.0.unpause();
+        let duration = timer.0.duration();
+        timer.0.set_elapsed(duration - Duration::from_nanos(1));
+        let weapon = ["laser", "spread", "beam", "missile"][frame % 4].to_string();
+        game.app.world.send_event(CreateLaserEvent { target: Vec3::new(0.0, 5.0, -30.0), weapon });
+        game.app.world.resource_mut::<Weapon>().reset();
+        game.step();
+        // without physics the shots never fly out of range, clear them for the laser cap
+        let lasers: Vec<Entity> = game.app.world.query_filtered::<Entity, With<Laser>>()
+            .iter(&game.app.world)
+            .collect();
+        shots += lasers.len();
+        for laser in lasers {
+            game.app.world.despawn(laser);
+        }
+        if frame % 100 == 99 {
+            counts.push((game.app.world.resource::<Assets<Mesh>>().len(),
+                         game.app.world.resource::<Assets<StandardMaterial>>().len()));
+        }
+    }
+
+    assert!(game.talon().cards.is_empty(), "the whole deck was dealt");
+    assert!(shots >= 600);
+    // nothing new after the first hundred frames, however many cards and shots follow
+    assert!(counts.iter().all(|count| *count == counts[0]), "{:?}", counts);
+    // a material per card face, the card quad and the shots
+    let (meshes, materials) = counts[0];
+    assert!(meshes <= 1 + 3 + 1, "{} meshes", meshes);
+    assert!(materials <= 52 + 3 + 4, "{} materials", materials);
+}
@@ -0,0 +1,17 @@
+use rust_bevy_air_ace::audio::AudioSettings;
+
+#[test]
+fn volumes_step_within_range_and_mute_silences_all() {
+    let mut settings = AudioSettings::default();
+    for _ in 0..20 {
+        AudioSettings::change(&mut settings.master, 1);
+    }
+    assert_eq!(settings.master, 1.0);
+    AudioSettings::change(&mut settings.music, -1);
+    assert!((settings.music - 0.4).abs() < 1e-5);
+    assert!((settings.music_volume() - 0.4).abs() < 1e-5);
+
+    settings.muted = true;
+    assert_eq!(settings.music_volume(), 0.0);
+    assert_eq!(settings.effects_volume(), 0.0);
+}
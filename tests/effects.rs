@@ -0,0 +1,40 @@
+use bevy::prelude::*;
+use rust_bevy_air_ace::card::{PlayingCard, Rank, Suit};
+use rust_bevy_air_ace::effects::ScreenShake;
+use rust_bevy_air_ace::harness::HeadlessGame;
+
+fn entity_count(game: &mut HeadlessGame) -> usize {
+    game.app.world.query::<Entity>().iter(&game.app.world).count()
+}
+
+#[test]
+fn rapid_hits_keep_sparks_and_assets_bounded() {
+    let mut game = HeadlessGame::new(6);
+    game.start();
+    let meshes = game.app.world.resource::<Assets<Mesh>>().len();
+    let materials = game.app.world.resource::<Assets<StandardMaterial>>().len();
+
+    for _ in 0..40 {
+        game.hit(PlayingCard::new(Suit::Spades, Rank::King));
+    }
+    let after_many_hits = entity_count(&mut game);
+    for _ in 0..40 {
+        game.hit(PlayingCard::new(Suit::Clubs, Rank::King));
+    }
+    assert!(entity_count(&mut game) <= after_many_hits + 2);
+    assert_eq!(game.app.world.resource::<Assets<Mesh>>().len(), meshes);
+    // one material per spark color
+    assert!(game.app.world.resource::<Assets<StandardMaterial>>().len() <= materials + 2);
+}
+
+#[test]
+fn only_mistakes_shake_the_screen() {
+    let mut game = HeadlessGame::new(7);
+    game.start();
+
+    game.hit(PlayingCard::new(Suit::Hearts, Rank::Two));
+    assert_eq!(game.app.world.resource::<ScreenShake>().strength, 0.0);
+
+    game.hit(PlayingCard::new(Suit::Spades, Rank::King));
+    assert!(game.app.world.resource::<ScreenShake>().strength > 0.0);
+}
@@ -0,0 +1,225 @@
+use rand::Rng;
+use rust_bevy_air_ace::card::{PlayingCard, Rank, Suit};
+use rust_bevy_air_ace::controls::Action;
+use rust_bevy_air_ace::difficulty::{CustomDifficulty, Difficulty, DifficultySettings, Escalation};
+use rust_bevy_air_ace::game::{GameRng, GameState};
+use rust_bevy_air_ace::highscore::{HighScore, HighScores, MAX_HIGHSCORES};
+use rust_bevy_air_ace::harness::HeadlessGame;
+use rust_bevy_air_ace::solitaire::{Card, SpawnTimer};
+use rust_bevy_air_ace::replay::{ReplayPlayer, ReplayRecorder};
+
+fn card(suit: Suit, rank: Rank) -> PlayingCard {
+    PlayingCard::new(suit, rank)
+}
+
+#[test]
+fn right_rank_advances_the_stack() {
+    let mut game = HeadlessGame::new(1);
+    game.start();
+    assert_eq!(game.state(), GameState::Playing);
+
+    game.hit(card(Suit::Hearts, Rank::Two));
+    assert_eq!(game.stack().current, 1);
+    game.hit(card(Suit::Clubs, Rank::Three));
+    assert_eq!(game.stack().current, 2);
+    assert_eq!(game.stack().texts, vec![card(Suit::Hearts, Rank::Two), card(Suit::Clubs, Rank::Three)]);
+}
+
+#[test]
+fn wrong_rank_rolls_the_stack_back() {
+    let mut game = HeadlessGame::new(2);
+    game.start();
+    let talon_size = game.talon().cards.len();
+
+    game.hit(card(Suit::Hearts, Rank::Two));
+    game.hit(card(Suit::Spades, Rank::King));
+    game.step();
+
+    assert_eq!(game.stack().current, 0);
+    assert!(game.stack().texts.is_empty());
+    // both the wrong card and the one taken off the stack go back to the talon
+    assert_eq!(game.talon().cards.len(), talon_size);
+}
+
+#[test]
+fn wrong_rank_on_empty_stack_keeps_it_empty() {
+    let mut game = HeadlessGame::new(3);
+    game.start();
+
+    game.hit(card(Suit::Diamonds, Rank::Ace));
+    assert_eq!(game.stack().current, 0);
+    assert_eq!(game.state(), GameState::Playing);
+}
+
+#[test]
+fn full_stack_ends_the_run() {
+    let mut game = HeadlessGame::new(4);
+    game.start();
+
+    for rank in Rank::ALL {
+        game.hit(card(Suit::Spades, rank));
+    }
+    game.step();
+    assert_eq!(game.state(), GameState::GameOver);
+}
+
+#[test]
+fn rules_decide_the_next_card() {
+    let mut game = HeadlessGame::new(5);
+    assert!(game.select_rules("A to 2"));
+    game.start();
+
+    game.hit(card(Suit::Hearts, Rank::Two));
+    assert_eq!(game.stack().current, 0);
+    game.hit(card(Suit::Hearts, Rank::Ace));
+    assert_eq!(game.stack().current, 1);
+}
+
+#[test]
+fn pausing_keeps_the_run() {
+    let mut game = HeadlessGame::new(6);
+    game.start();
+    game.hit(card(Suit::Hearts, Rank::Two));
+
+    game.pause();
+    assert_eq!(game.state(), GameState::Paused);
+    let paused_at = game.score().time.elapsed();
+    game.step();
+    game.step();
+    assert_eq!(game.score().time.elapsed(), paused_at);
+
+    game.resume();
+    assert_eq!(game.state(), GameState::Playing);
+    assert!(game.score().time.elapsed() > paused_at);
+    assert_eq!(game.stack().current, 1);
+    game.hit(card(Suit::Hearts, Rank::Three));
+    assert_eq!(game.stack().current, 2);
+}
+
+#[test]
+fn replay_can_be_watched_from_the_game_over_screen() {
+    let mut game = HeadlessGame::new(10);
+    game.start();
+    for rank in Rank::ALL {
+        game.hit(card(Suit::Spades, rank));
+    }
+    game.step();
+    assert_eq!(game.state(), GameState::GameOver);
+
+    game.press(Action::WatchReplay);
+    game.step();
+    assert_eq!(game.state(), GameState::GameStart);
+    let player = game.app.world.get_resource::<ReplayPlayer>().expect("the replay is about to play");
+    assert_eq!(player.replay.seed, 10);
+}
+
+#[test]
+fn replay_plays_the_recorded_run_again() {
+    let mut recorded = HeadlessGame::new(11);
+    recorded.start();
+    recorded.app.world.resource_mut::<SpawnTimer>().0.unpause();
+    for _ in 0..900 {
+        recorded.step();
+    }
+    let replay = recorded.app.world.resource::<ReplayRecorder>().replay.clone();
+    assert!(replay.events.len() > 3);
+
+    let mut watched = HeadlessGame::new(99);
+    watched.app.insert_resource(ReplayPlayer::new(replay.clone()));
+    watched.start();
+    watched.app.world.resource_mut::<SpawnTimer>().0.unpause();
+    // the last recorded frame; the next step ends the replay and drops the player
+    while watched.app.world.resource::<ReplayPlayer>().frame < replay.frames.len() {
+        watched.step();
+    }
+    assert_eq!(watched.app.world.resource::<ReplayPlayer>().desyncs, 0);
+
+    assert_eq!(watched.stack().texts, recorded.stack().texts);
+    assert_eq!(watched.talon().cards, recorded.talon().cards);
+    assert_eq!(watched.score().mistakes, recorded.score().mistakes);
+    assert_eq!(watched.score().time.elapsed(), recorded.score().time.elapsed());
+    let cards = |game: &mut HeadlessGame| game.app.world.query::<&Card>().iter(&game.app.world).count();
+    assert_eq!(cards(&mut watched), cards(&mut recorded));
+}
+
+#[test]
+fn best_times_are_kept_per_difficulty() {
+    let mut game = HeadlessGame::new(7);
+    game.select_difficulty(Difficulty::Hard);
+    game.start();
+    let hard = Difficulty::Hard.settings(&CustomDifficulty::default());
+    assert_eq!(*game.app.world.resource::<DifficultySettings>(), hard);
+
+    for rank in Rank::ALL {
+        game.hit(card(Suit::Spades, rank));
+    }
+    game.step();
+    assert_eq!(game.state(), GameState::GameOver);
+
+    let highscores = game.app.world.resource::<HighScores>();
+    assert!(highscores.best("2 to A", Difficulty::Hard).is_some());
+    assert!(highscores.best("2 to A", Difficulty::Normal).is_none());
+}
+
+#[test]
+fn full_table_leaves_other_difficulties_their_places() {
+    let run = |time: f32, difficulty: Difficulty| HighScore {
+        time,
+        date: "2024-01-01".to_string(),
+        seed: 0,
+        rules: "2 to A".to_string(),
+        mistakes: 0,
+        difficulty,
+    };
+    let mut highscores = HighScores::default();
+    for i in 0..MAX_HIGHSCORES + 2 {
+        highscores.insert(run(10.0 + i as f32, Difficulty::Easy));
+    }
+    assert_eq!(highscores.insert(run(100.0, Difficulty::Hard)), Some(0));
+    assert_eq!(highscores.insert(run(50.0, Difficulty::Easy)), None);
+
+    assert_eq!(highscores.best("2 to A", Difficulty::Hard), Some(100.0));
+    assert_eq!(highscores.best("2 to A", Difficulty::Easy), Some(10.0));
+    let easy = highscores.entries.iter().filter(|e| e.difficulty == Difficulty::Easy).count();
+    assert_eq!(easy, MAX_HIGHSCORES);
+    let hard = highscores.table("2 to A", Difficulty::Hard);
+    assert!(hard.contains("(hard)") && hard.contains(" 1.  100.0s"), "{}", hard);
+    assert!(!hard.contains("10.0s"), "{}", hard);
+}
+
+#[test]
+fn ramp_escalates_as_the_stack_fills() {
+    let mut game = HeadlessGame::new(8);
+    game.select_difficulty(Difficulty::Hard);
+    game.start();
+    game.step();
+    let start = *game.app.world.resource::<Escalation>();
+    assert_eq!(start.decoy_chance, 0.0);
+
+    for rank in &Rank::ALL[..10] {
+        game.hit(card(Suit::Clubs, *rank));
+    }
+    game.step();
+    let late = *game.app.world.resource::<Escalation>();
+    assert!(late.spawn_interval < start.spawn_interval);
+    assert!(late.gravity_scale > start.gravity_scale);
+    assert!(late.decoy_chance > 0.0);
+}
+
+#[test]
+fn effects_do_not_change_the_card_sequence() {
+    let draws = |hits: bool| {
+        let mut game = HeadlessGame::new(9);
+        game.start();
+        for rank in &Rank::ALL[..4] {
+            if hits {
+                game.hit(card(Suit::Hearts, *rank));
+            } else {
+                game.step();
+            }
+        }
+        let mut game_rng = game.app.world.resource_mut::<GameRng>();
+        (0..20).map(|_| game_rng.rng.gen::<u32>()).collect::<Vec<_>>()
+    };
+    assert_eq!(draws(true), draws(false));
+}
@@ -0,0 +1,34 @@
+use rust_bevy_air_ace::card::{PlayingCard, Rank, Suit};
+use rust_bevy_air_ace::harness::HeadlessGame;
+use rust_bevy_air_ace::powerup::{PowerUp, PowerUps};
+
+#[test]
+fn shield_takes_a_wrong_hit() {
+    let mut game = HeadlessGame::new(4);
+    game.start();
+
+    game.hit(PlayingCard::new(Suit::Hearts, Rank::Two));
+    game.collect(PowerUp::Shield);
+    assert_eq!(game.app.world.resource::<PowerUps>().shields, 1);
+
+    game.hit(PlayingCard::new(Suit::Spades, Rank::King));
+    assert_eq!(game.score().mistakes, 0);
+    assert_eq!(game.stack().current, 1);
+    assert_eq!(game.app.world.resource::<PowerUps>().shields, 0);
+
+    // without a shield the next wrong hit counts again
+    game.hit(PlayingCard::new(Suit::Spades, Rank::Queen));
+    assert_eq!(game.score().mistakes, 1);
+}
+
+#[test]
+fn wildcard_places_the_next_card() {
+    let mut game = HeadlessGame::new(5);
+    game.start();
+    let talon_size = game.talon().cards.len();
+
+    game.collect(PowerUp::Wildcard);
+    assert_eq!(game.stack().current, 1);
+    assert_eq!(game.stack().texts[0].rank(), Some(Rank::Two));
+    assert_eq!(game.talon().cards.len(), talon_size - 1);
+}
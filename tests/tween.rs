@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+use rust_bevy_air_ace::card::{PlayingCard, Rank, Suit};
+use rust_bevy_air_ace::game::GameState;
+use rust_bevy_air_ace::harness::HeadlessGame;
+use rust_bevy_air_ace::solitaire::{card_slot, Card};
+use rust_bevy_air_ace::tween::{CardTweening, Ease, Tween};
+
+#[test]
+fn eases_start_at_zero_and_end_at_one() {
+    for ease in [Ease::Linear, Ease::QuadIn, Ease::CubicOut, Ease::BackOut] {
+        assert!(ease.apply(0.0).abs() < 1e-5, "{:?}", ease);
+        assert!((ease.apply(1.0) - 1.0).abs() < 1e-5, "{:?}", ease);
+    }
+    assert!(Ease::BackOut.apply(0.8) > 1.0);
+}
+
+#[test]
+fn card_lands_exactly_in_its_slot() {
+    let from = Transform::from_xyz(3.0, 5.0, -24.0).with_rotation(Quat::from_rotation_z(0.7));
+    let slot = Vec3::new(-15.0, -12.5, -28.0);
+    let mut tween = CardTweening::default().place(from, slot);
+
+    tween.advance(tween.duration / 2.0);
+    assert!(!tween.is_done());
+
+    let end = tween.advance(tween.duration);
+    assert!(tween.is_done());
+    assert!(end.translation.distance(slot) < 1e-4);
+    assert!(end.rotation.angle_between(Quat::IDENTITY) < 1e-4);
+}
+
+#[test]
+fn arc_rises_above_the_straight_way() {
+    let from = Transform::from_xyz(0.0, 0.0, 0.0);
+    let to = Transform::from_xyz(4.0, 0.0, 0.0);
+    let mut tween = Tween::new(from, to, 1.0).with_ease(Ease::Linear).with_arc(2.0);
+    let halfway = tween.advance(0.5);
+    assert!((halfway.translation - Vec3::new(2.0, 2.0, 0.0)).length() < 1e-4);
+}
+
+#[test]
+fn last_card_lands_before_the_game_over_screen() {
+    let mut game = HeadlessGame::new(12);
+    game.start();
+    for rank in Rank::ALL {
+        game.hit(PlayingCard::new(Suit::Spades, rank));
+    }
+    game.step();
+    assert_eq!(game.state(), GameState::GameOver);
+
+    let world = &mut game.app.world;
+    assert_eq!(world.query::<&Tween>().iter(world).count(), 0);
+    let ace = PlayingCard::new(Suit::Spades, Rank::Ace);
+    let (transform, _) = world.query::<(&Transform, &Card)>().iter(world)
+        .find(|(_, card)| card.face == ace)
+        .expect("the ace is on the stack");
+    assert!(transform.translation.distance(card_slot(1, 0, 12)) < 1e-4);
+}
@@ -0,0 +1,36 @@
+use rust_bevy_air_ace::weapon::{HomingMissile, SingleLaser, Weapon, WeaponKind};
+
+#[test]
+fn holding_the_trigger_overheats_and_locks_the_weapon() {
+    let mut weapon = Weapon::default();
+    let frame = 1.0 / 60.0;
+    let mut shots = 0;
+    while !weapon.is_overheated() {
+        if weapon.ready() {
+            weapon.shoot(&SingleLaser);
+            shots += 1;
+        }
+        weapon.cool(frame);
+        assert!(shots < 100, "weapon never overheats");
+    }
+    assert!(shots > 1);
+
+    // locked until the cooldown is over, then free with a cold barrel
+    let mut locked_frames = 0;
+    while !weapon.ready() {
+        weapon.cool(frame);
+        locked_frames += 1;
+    }
+    assert!(locked_frames as f32 * frame >= weapon.overheat_time - frame);
+    assert_eq!(weapon.heat, 0.0);
+}
+
+#[test]
+fn heavier_weapons_heat_up_faster() {
+    let mut laser = Weapon::default();
+    laser.shoot(&SingleLaser);
+    let mut missile = Weapon::default();
+    missile.shoot(&HomingMissile);
+    assert!(missile.heat > laser.heat);
+    assert!(HomingMissile.unlocks_at() > SingleLaser.unlocks_at());
+}